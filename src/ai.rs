@@ -1,76 +1,421 @@
 use std::vec::*;
+use std::rand::{Rng, XorShiftRng};
+use extra::time::precise_time_ns;
+use bitboard::*;
 use board::*;
+use evaluator::*;
+use learning::*;
+use mcts::*;
+use menace::*;
+use rules::*;
+use strategy::*;
+use transposition::*;
 
-#[deriving(Clone)]
-pub enum Strategy {
-    Minimax,
-    LowestAvailable
+pub static INFINITY: int = 1000000;
+
+pub struct Minimax {
+    ai: AI
+}
+
+impl Minimax {
+    pub fn new() -> Minimax {
+        Minimax::with_ai(AI::new())
+    }
+
+    pub fn with_ai(ai: AI) -> Minimax {
+        Minimax { ai: ai }
+    }
+}
+
+impl Strategy for Minimax {
+    fn name(&self) -> ~str {
+        ~"Minimax"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        self.ai.alpha_beta(Bitboard::from_board(board))
+    }
+}
+
+pub struct LowestAvailable;
+
+impl Strategy for LowestAvailable {
+    fn name(&self) -> ~str {
+        ~"Lowest available"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        board.spaces.iter().position( |space: &Cell| *space == Empty ).map( |&index: &uint| index as int )
+    }
+}
+
+pub struct RandomAvailable {
+    ai: AI
+}
+
+impl RandomAvailable {
+    pub fn new() -> RandomAvailable {
+        RandomAvailable::with_ai(AI::new())
+    }
+
+    pub fn with_ai(ai: AI) -> RandomAvailable {
+        RandomAvailable { ai: ai }
+    }
+}
+
+impl Strategy for RandomAvailable {
+    fn name(&self) -> ~str {
+        ~"Random"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        self.ai.get_random_available_index(board)
+    }
+}
+
+// Always takes a win, but only sees the opponent's threats some of the
+// time and otherwise plays anywhere.
+pub struct Careless {
+    ai: AI,
+    block_chance: float
+}
+
+impl Careless {
+    pub fn new(block_chance: float) -> Careless {
+        Careless::with_ai(AI::new(), block_chance)
+    }
+
+    pub fn with_ai(ai: AI, block_chance: float) -> Careless {
+        Careless { ai: ai, block_chance: block_chance }
+    }
+}
+
+impl Strategy for Careless {
+    fn name(&self) -> ~str {
+        ~"Careless"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        self.ai.careless_move(board, self.block_chance)
+    }
+}
+
+// Plays perfectly except for the occasional random move.
+pub struct Fallible {
+    ai: AI,
+    error_chance: float
+}
+
+impl Fallible {
+    pub fn new(error_chance: float) -> Fallible {
+        Fallible::with_ai(AI::new(), error_chance)
+    }
+
+    pub fn with_ai(ai: AI, error_chance: float) -> Fallible {
+        Fallible { ai: ai, error_chance: error_chance }
+    }
+}
+
+impl Strategy for Fallible {
+    fn name(&self) -> ~str {
+        ~"Fallible"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        self.ai.fallible_move(board, self.error_chance)
+    }
+}
+
+pub struct DepthLimited {
+    ai: AI,
+    max_depth: int,
+    evaluator: @Evaluator
+}
+
+impl DepthLimited {
+    pub fn new(max_depth: int, evaluator: @Evaluator) -> DepthLimited {
+        DepthLimited::with_ai(AI::new(), max_depth, evaluator)
+    }
+
+    pub fn with_ai(ai: AI, max_depth: int, evaluator: @Evaluator) -> DepthLimited {
+        DepthLimited { ai: ai, max_depth: max_depth, evaluator: evaluator }
+    }
+}
+
+impl Strategy for DepthLimited {
+    fn name(&self) -> ~str {
+        ~"Depth limited"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        self.ai.search(Bitboard::from_board(board), &mut Search::new(Ply(self.max_depth, self.evaluator)))
+    }
+}
+
+// Spends up to `budget` milliseconds on each move.
+pub struct TimeLimited {
+    ai: AI,
+    budget: u64,
+    evaluator: @Evaluator
+}
+
+impl TimeLimited {
+    pub fn new(budget: u64, evaluator: @Evaluator) -> TimeLimited {
+        TimeLimited::with_ai(AI::new(), budget, evaluator)
+    }
+
+    pub fn with_ai(ai: AI, budget: u64, evaluator: @Evaluator) -> TimeLimited {
+        TimeLimited { ai: ai, budget: budget, evaluator: evaluator }
+    }
+}
+
+impl Strategy for TimeLimited {
+    fn name(&self) -> ~str {
+        ~"Time limited"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        self.ai.iterative_deepening(Bitboard::from_board(board), self.budget, self.evaluator)
+    }
+}
+
+pub struct MonteCarlo {
+    ai: AI,
+    budget: Budget,
+    exploration: float
+}
+
+impl MonteCarlo {
+    pub fn new(budget: Budget, exploration: float) -> MonteCarlo {
+        MonteCarlo::with_ai(AI::new(), budget, exploration)
+    }
+
+    pub fn with_ai(ai: AI, budget: Budget, exploration: float) -> MonteCarlo {
+        MonteCarlo { ai: ai, budget: budget, exploration: exploration }
+    }
+}
+
+impl Strategy for MonteCarlo {
+    fn name(&self) -> ~str {
+        ~"Monte Carlo"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        SearchTree::search(Bitboard::from_board(board), self.budget, self.exploration, &mut *self.ai.rng)
+    }
+}
+
+pub struct RuleBased;
+
+impl Strategy for RuleBased {
+    fn name(&self) -> ~str {
+        ~"Rule based"
+    }
+
+    // Where no rule applies on a larger board, it takes the lowest free space.
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        match board.rule_based_move() {
+            Some((index, _)) => Some(index),
+            None             => LowestAvailable.choose_move(board)
+        }
+    }
+
+    fn explain(&self, board: &Board) -> Option<~str> {
+        board.rule_based_move().map( |&(_, rule)| rule.describe() )
+    }
+}
+
+pub struct Learned {
+    table: @ValueTable
+}
+
+impl Learned {
+    pub fn new(table: @ValueTable) -> Learned {
+        Learned { table: table }
+    }
 }
 
+impl Strategy for Learned {
+    fn name(&self) -> ~str {
+        ~"Learned"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        self.table.best_move(board)
+    }
+}
+
+pub struct Menace {
+    ai: AI,
+    matchboxes: @mut Matchboxes
+}
+
+impl Menace {
+    pub fn new(matchboxes: @mut Matchboxes) -> Menace {
+        Menace::with_ai(AI::new(), matchboxes)
+    }
+
+    pub fn with_ai(ai: AI, matchboxes: @mut Matchboxes) -> Menace {
+        Menace { ai: ai, matchboxes: matchboxes }
+    }
+}
+
+impl Strategy for Menace {
+    fn name(&self) -> ~str {
+        ~"MENACE"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        self.matchboxes.choose_move(board, &mut *self.ai.rng)
+    }
+
+    // Saved after every game, since quitting ends the program.
+    fn game_over(&self, outcome: &GameOutcome, token: Token) -> Result<(), ~str> {
+        self.matchboxes.learn(outcome, token);
+        self.matchboxes.save()
+    }
+}
+
+// How far a search looks: to the end of the game, or a number of moves
+// after which the evaluator scores the position.
+pub enum Horizon {
+    EndOfGame,
+    Ply(int, @Evaluator)
+}
+
+impl Horizon {
+    pub fn remaining(&self, depth: int) -> int {
+        match *self {
+            EndOfGame         => INFINITY,
+            Ply(max_depth, _) => max_depth - depth
+        }
+    }
+}
+
+pub struct Search {
+    horizon: Horizon,
+    deadline: Option<u64>,
+    timed_out: bool
+}
+
+impl Search {
+    pub fn new(horizon: Horizon) -> Search {
+        Search { horizon: horizon, deadline: None, timed_out: false }
+    }
+
+    pub fn with_deadline(horizon: Horizon, deadline: u64) -> Search {
+        Search { horizon: horizon, deadline: Some(deadline), timed_out: false }
+    }
+
+    fn out_of_time(&mut self) -> bool {
+        match self.deadline {
+            Some(deadline) if precise_time_ns() >= deadline => self.timed_out = true,
+            _ => ()
+        }
+
+        self.timed_out
+    }
+}
+
+// The searches the strategies share. Clones share the transposition table
+// and random number generator.
 pub struct AI {
-    strategy: Strategy
+    table: @mut TranspositionTable,
+    rng: @mut XorShiftRng,
+    random_ties: bool
 }
 
 impl AI {
-    pub fn new(strategy: Strategy) -> AI {
-        AI { strategy: strategy }
+    pub fn new() -> AI {
+        AI { table: @mut TranspositionTable::new(),
+             rng: @mut XorShiftRng::new(),
+             random_ties: false }
     }
 
-    pub fn get_move(&self, board: Board) -> Option<int> {
-        ::std::rt::io::timer::sleep(1000); // simulate thinking
+    // Picks at random among equally good moves, with random numbers that
+    // come out the same for the same seed so a game can be replayed.
+    pub fn seeded(seed: u32) -> AI {
+        AI { table: @mut TranspositionTable::new(),
+             rng: @mut XorShiftRng::new_seeded(seed, 362436069, 521288629, 88675123),
+             random_ties: true }
+    }
+
+    fn get_random_available_index(&self, board: &Board) -> Option<int> {
+        let spaces = board.available_spaces();
 
-        match (*self).strategy {
-            LowestAvailable => self.get_lowest_available_index(board.spaces.clone()),
-            Minimax         => self.minimax(board)
+        if spaces.is_empty() {
+            None
+        } else {
+            Some(spaces[self.rng.gen_integer_range(0u, spaces.len())])
         }
     }
 
-    fn get_lowest_available_index(&self, spaces: ~[char]) -> Option<int> {
-        let empty_spaces = spaces.clone().to_owned();
-        let position = empty_spaces.iter().position( |x: &char| *x == ' ' );
+    fn careless_move(&self, board: &Board, block_chance: float) -> Option<int> {
+        let token = board.current_token();
+        let wins = board.winning_moves(token);
+        let blocks = board.winning_moves(token.other());
 
-        match position {
-            Some(index) => Some(index as int),
-            None        => None
+        if !wins.is_empty() {
+            Some(wins[0])
+        } else if !blocks.is_empty() && self.chance(block_chance) {
+            Some(blocks[0])
+        } else {
+            self.get_random_available_index(board)
         }
     }
 
-    pub fn clone(&self) -> AI {
-        AI { strategy: self.strategy.clone() }
+    fn fallible_move(&self, board: &Board, error_chance: float) -> Option<int> {
+        if self.chance(error_chance) {
+            self.get_random_available_index(board)
+        } else {
+            self.alpha_beta(Bitboard::from_board(board))
+        }
     }
 
-    fn minimax(&self, board: Board) -> Option<int> {
-        let scores = self.get_all_scores(board, 0);
+    fn chance(&self, probability: float) -> bool {
+        self.rng.gen::<float>() < probability
+    }
 
-        Some(self.index_of_best_score(scores))
+    pub fn clone(&self) -> AI {
+        AI { table: self.table,
+             rng: self.rng,
+             random_ties: self.random_ties }
     }
 
-    fn get_all_scores(&self, board: Board, depth: int) -> ~[Option<int>] {
-        let mut scores: ~[Option<int>] = ~[None, ..9];
+    // Shares the table but draws its own random numbers, seeded from these,
+    // so however many it draws never changes what the others draw.
+    pub fn forked(&self) -> AI {
+        let rng = XorShiftRng::new_seeded(self.rng.gen::<u32>(), self.rng.gen::<u32>(),
+                                          self.rng.gen::<u32>(), self.rng.gen::<u32>() | 1);
 
-        let mut i = 0;
-        let available_indexes = board.clone().available_spaces();
+        AI { table: self.table,
+             rng: @mut rng,
+             random_ties: self.random_ties }
+    }
 
-        loop {
-            if available_indexes.contains(&i) {
-                scores[i] = Some(self.score_move(i, board.clone(), depth));
-            }
+    fn minimax(&self, board: Bitboard) -> Option<int> {
+        let scores = self.get_all_scores(board, 0);
 
-            i += 1;
+        self.index_of_best_score(scores)
+    }
+
+    fn get_all_scores(&self, board: Bitboard, depth: int) -> ~[Option<int>] {
+        let mut scores: ~[Option<int>] = from_elem(board.len(), None);
 
-            if (i as uint) > board.spaces.len() { break }
+        for &index in board.available_spaces().iter() {
+            scores[index] = Some(self.score_move(index, board.clone(), depth));
         }
 
         scores
     }
 
-    pub fn score_move(&self, index: int, board: Board, depth: int) -> int {
+    pub fn score_move(&self, index: int, board: Bitboard, depth: int) -> int {
         let new_board = board.place(index);
 
         self.assign_score_to_board(new_board, depth)
     }
 
-    fn assign_score_to_board(&self, board: Board, depth: int) -> int {
+    fn assign_score_to_board(&self, board: Bitboard, depth: int) -> int {
         if board.is_game_over() {
             self.score_finished_board(board.clone(), depth)
         } else {
@@ -78,55 +423,232 @@ impl AI {
         }
     }
 
-    fn best_score_from_remaining_spaces(&self, board: Board, depth: int) -> int {
+    fn best_score_from_remaining_spaces(&self, board: Bitboard, depth: int) -> int {
         let scores = self.get_all_scores(board.clone(), depth + 1);
-        let max_value = scores.iter().max().unwrap().unwrap();
+        let max_value = scores.iter().filter_map( |score: &Option<int>| *score ).max().unwrap();
 
         max_value * -1
     }
 
-    pub fn score_finished_board(&self, board: Board, depth: int) -> int {
-        match board.winner() {
-            Some(*) => 9 - depth,
-            None         => 0
+    fn alpha_beta(&self, board: Bitboard) -> Option<int> {
+        self.search(board, &mut Search::new(EndOfGame))
+    }
+
+    // Searches one move deeper each time, until the budget in milliseconds
+    // runs out or the search reaches the end of the game, and plays the move
+    // from the deepest search that finished.
+    fn iterative_deepening(&self, board: Bitboard, budget: u64, evaluator: @Evaluator) -> Option<int> {
+        let deadline = precise_time_ns() + budget * 1000000;
+        let moves_left = board.available_spaces().len() as int;
+        let mut best_index = None;
+        let mut max_depth = 1;
+
+        while max_depth <= moves_left {
+            let mut search = Search::with_deadline(Ply(max_depth, evaluator), deadline);
+            let index = self.search(board.clone(), &mut search);
+
+            if search.timed_out {
+                break;
+            }
+
+            best_index = index;
+            max_depth += 1;
         }
+
+        best_index
     }
 
-    fn index_of_best_score(&self, scores: ~[Option<int>]) -> int {
-        let unwrapped_scores = self.map_try_unwrap(scores.clone());
+    // To break ties at random, moves are searched with a window just below
+    // the best score so far, so every move as good as it gets an exact score.
+    fn search(&self, board: Bitboard, search: &mut Search) -> Option<int> {
+        let mut scored_moves = ~[];
+        let mut best_score = -INFINITY;
+
+        for &index in board.available_spaces().iter() {
+            let alpha = if self.random_ties { best_score - 1 } else { best_score };
+            let score = self.alpha_beta_score_move(index, board.clone(), 0, alpha, INFINITY, search);
+
+            if score > best_score {
+                best_score = score;
+            }
+
+            scored_moves.push((index, score));
+        }
 
-        self.index_of_max_score(unwrapped_scores)
+        self.index_of_max_score(scored_moves)
     }
 
-    fn index_of_max_score(&self, scores: ~[int]) -> int {
-        let max_value = scores.iter().max().unwrap();
+    pub fn alpha_beta_score_move(&self, index: int, board: Bitboard, depth: int, alpha: int, beta: int,
+                                 search: &mut Search) -> int {
+        let new_board = board.place(index);
 
-        scores.iter().position( |x: &int| *x == *max_value ).unwrap() as int
+        if new_board.is_game_over() {
+            self.score_finished_board(new_board, depth) * WIN_SCALE
+        } else {
+            -self.negamax(new_board, depth + 1, -beta, -alpha, search)
+        }
     }
 
-    fn try_unwrap(&self, box: Option<int>) -> int {
-        if box == None {
-            -1000
+    fn negamax(&self, board: Bitboard, depth: int, alpha: int, beta: int, search: &mut Search) -> int {
+        match search.horizon {
+            Ply(max_depth, evaluator) if depth >= max_depth => return self.score_unfinished_board(&board, evaluator),
+            _ => ()
+        }
+
+        // The score no longer matters once the search is abandoned.
+        if search.out_of_time() {
+            return 0;
+        }
+
+        let mut alpha = alpha;
+        let mut beta = beta;
+        let hash = board.hash();
+
+        match self.table.find(hash) {
+            Some(entry) if entry.draft >= search.horizon.remaining(depth) => {
+                let score = from_table_score(entry.score, depth);
+
+                match entry.bound {
+                    Exact      => return score,
+                    LowerBound => if score > alpha { alpha = score },
+                    UpperBound => if score < beta { beta = score }
+                }
+
+                if alpha >= beta {
+                    return score;
+                }
+            },
+
+            _ => ()
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+
+        for &index in board.available_spaces().iter() {
+            let score = self.alpha_beta_score_move(index, board.clone(), depth, alpha, beta, search);
+
+            if score > best_score {
+                best_score = score;
+            }
+
+            if best_score > alpha {
+                alpha = best_score;
+            }
+
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best_score <= original_alpha {
+            UpperBound
+        } else if best_score >= beta {
+            LowerBound
         } else {
-            box.unwrap()
+            Exact
+        };
+
+        if !search.timed_out {
+            self.table.store(hash, Entry { score: to_table_score(best_score, depth),
+                                           bound: bound,
+                                           draft: search.horizon.remaining(depth) });
         }
+
+        best_score
+    }
+
+    // Kept short of the slowest win or loss, so a guess never outweighs a
+    // result the search has actually found.
+    fn score_unfinished_board(&self, board: &Bitboard, evaluator: @Evaluator) -> int {
+        let score = evaluator.evaluate(board, board.current_token());
+
+        score.max(&(1 - WIN_SCALE)).min(&(WIN_SCALE - 1))
+    }
+
+    pub fn score_finished_board(&self, board: Bitboard, depth: int) -> int {
+        match board.winner() {
+            Some(*) => board.len() as int - depth,
+            None         => 0
+        }
+    }
+
+    // Occupied spaces have no score and are never the best.
+    fn index_of_best_score(&self, scores: ~[Option<int>]) -> Option<int> {
+        let scored_moves = scores.iter().enumerate().filter_map( |(index, &score): (uint, &Option<int>)|
+            score.map( |&score: &int| (index as int, score) )
+        ).collect::<~[(int, int)]>();
+
+        self.index_of_max_score(scored_moves)
+    }
+
+    fn index_of_max_score(&self, scored_moves: ~[(int, int)]) -> Option<int> {
+        let best_score = match scored_moves.iter().map( |&(_, score): &(int, int)| score ).max() {
+            Some(score) => score,
+            None        => return None
+        };
+
+        let best_moves = scored_moves.iter().filter( |&&(_, score): &&(int, int)| score == best_score )
+                                            .map( |&(index, _): &(int, int)| index )
+                                            .collect::<~[int]>();
+
+        self.break_tie(best_moves)
     }
 
-    fn map_try_unwrap(&self, scores: ~[Option<int>]) -> ~[int] {
-        scores.iter().map(|&score| self.try_unwrap(score)).to_owned_vec()
+    // The first of equally good moves, or any of them when ties are random.
+    pub fn break_tie(&self, moves: &[int]) -> Option<int> {
+        if moves.is_empty() {
+            None
+        } else if self.random_ties {
+            Some(moves[self.rng.gen_integer_range(0u, moves.len())])
+        } else {
+            Some(moves[0])
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use bitboard::*;
     use board::*;
+    use evaluator::*;
+    use learning::*;
+    use mcts::*;
+    use menace::*;
+    use notation::*;
+    use std::hashmap::HashSet;
+    use strategy::*;
+
+    fn bitboard(spaces: ~[char]) -> Bitboard {
+        Bitboard::from_board(&Board::new_from_spaces(spaces))
+    }
+
+    fn reachable_positions() -> ~[Board] {
+        let mut seen = HashSet::new();
+        let mut positions = ~[];
+        let mut frontier = ~[Board::new(), Board::new().with_to_move(O)];
+
+        while !frontier.is_empty() {
+            let board = frontier.pop();
+
+            if !board.is_game_over() && seen.insert(board.to_notation()) {
+                for &index in board.available_spaces().iter() {
+                    frontier.push(board.place(index));
+                }
+
+                positions.push(board);
+            }
+        }
+
+        positions
+    }
 
-    fn setup_ai() -> (AI, Board) {
-        let ai = AI::new(Minimax);
-        let example_board = Board::new_from_spaces(~['o','x','o',
-                                                     'o','x','x',
-                                                     ' ',' ',' ' ]);
+    fn setup_ai() -> (AI, Bitboard) {
+        let ai = AI::new();
+        let example_board = bitboard(~['o','x','o',
+                                       'o','x','x',
+                                       ' ',' ',' ' ]);
 
         (
             ai,
@@ -136,19 +658,19 @@ mod test {
 
     #[test]
     fn can_score_a_finished_board() {
-        let ai = AI::new(Minimax);
+        let ai = AI::new();
 
-        let tie_board = Board::new_from_spaces(~['o','x','o',
-                                                 'o','x','x',
-                                                 'x','o','x' ]);
+        let tie_board = bitboard(~['o','x','o',
+                                   'o','x','x',
+                                   'x','o','x' ]);
 
-        let x_wins_board = Board::new_from_spaces(~['o','x','x',
-                                                    'o','o','x',
-                                                    'x','o','x' ]);
+        let x_wins_board = bitboard(~['o','x','x',
+                                      'o','o','x',
+                                      'x','o','x' ]);
 
-        let o_wins_board = Board::new_from_spaces(~['o','x','x',
-                                                    'x','o','o',
-                                                    'x',' ','o' ]);
+        let o_wins_board = bitboard(~['o','x','x',
+                                      'x','o','o',
+                                      'x',' ','o' ]);
 
         let tie_board_score = ai.assign_score_to_board(tie_board, 0);
         let x_wins_score = ai.assign_score_to_board(x_wins_board, 0);
@@ -181,20 +703,20 @@ mod test {
 
     #[test]
     fn scores_tying_moves_as_0() {
-        let ai = AI::new(Minimax);
-        let board = Board::new_from_spaces(~['o','x','o',
-                                             'o','x','x',
-                                             'x','o',' ' ]);
+        let ai = AI::new();
+        let board = bitboard(~['o','x','o',
+                               'o','x','x',
+                               'x','o',' ' ]);
 
         assert_eq!(0, ai.score_move(8, board.clone(), 0));
     }
 
     #[test]
     fn scores_winning_moves_positively() {
-        let ai = AI::new(Minimax);
-        let board = Board::new_from_spaces(~['o','x','o',
-                                             'o','x','x',
-                                             ' ',' ',' ' ]);
+        let ai = AI::new();
+        let board = bitboard(~['o','x','o',
+                               'o','x','x',
+                               ' ',' ',' ' ]);
 
         let winning_score = ai.score_move(7, board.clone(), 0);
 
@@ -203,10 +725,10 @@ mod test {
 
     #[test]
     fn scores_losing_moves_negatively() {
-        let ai = AI::new(Minimax);
-        let board = Board::new_from_spaces(~['o','x','x',
-                                             'o','x','o',
-                                             ' ',' ','x' ]);
+        let ai = AI::new();
+        let board = bitboard(~['o','x','x',
+                               'o','x','o',
+                               ' ',' ','x' ]);
 
         let losing_score = ai.score_move(7, board.clone(), 0);
 
@@ -215,10 +737,10 @@ mod test {
 
     #[test]
     fn accounts_for_depth() {
-        let ai = AI::new(Minimax);
-        let board = Board::new_from_spaces(~['x','o','x',
-                                             'o','x','o',
-                                             ' ',' ',' ' ]);
+        let ai = AI::new();
+        let board = bitboard(~['x','o','x',
+                               'o','x','o',
+                               ' ',' ',' ' ]);
 
         let fast_win1 = ai.score_move(6, board.clone(), 0);
         let fast_win2 = ai.score_move(8, board.clone(), 0);
@@ -230,33 +752,440 @@ mod test {
 
     #[test]
     fn can_pick_the_best_move_from_the_next_turn() {
-        let ai = AI::new(Minimax);
-        let board = Board::new_from_spaces(~['x','o','x',
-                                             'o','x','x',
-                                             ' ',' ','o' ]);
+        let ai = AI::new();
+        let board = bitboard(~['x','o','x',
+                               'o','x','x',
+                               ' ',' ','o' ]);
+
+        let scores = ai.get_all_scores(board, 0);
+
+        assert_eq!(Some(6), ai.index_of_best_score(scores));
+    }
+
+    #[test]
+    fn picks_among_tied_scores_at_random_when_seeded() {
+        let ai = AI::seeded(7);
+        let mut picked = ~[];
+
+        do 50.times {
+            let index = ai.index_of_max_score(~[(0, 1), (3, 5), (4, 2), (7, 5)]).unwrap();
+
+            assert!(index == 3 || index == 7);
+
+            if !picked.contains(&index) {
+                picked.push(index);
+            }
+        }
+
+        assert_eq!(2, picked.len());
+        assert_eq!(Some(3), AI::new().index_of_max_score(~[(0, 1), (3, 5), (4, 2), (7, 5)]));
+    }
+
+    #[test]
+    fn has_no_best_score_without_a_free_space() {
+        let ai = AI::new();
+
+        assert_eq!(None, ai.index_of_best_score(~[None, None, None]));
+    }
+
+    #[test]
+    fn scores_every_space_on_a_larger_board() {
+        let ai = AI::new();
+        let board = bitboard(~['x','o','x','o',
+                               'o','x','o','x',
+                               'x','o','x','o',
+                               'o','x',' ',' ' ]);
 
         let scores = ai.get_all_scores(board, 0);
 
-        assert_eq!(6, ai.index_of_best_score(scores));
+        assert_eq!(16, scores.len());
+        assert_eq!(None, scores[13]);
+        assert!(scores[14].is_some());
+        assert!(scores[15].is_some());
     }
 
     #[test]
     fn can_run_the_whole_algorithm() {
-        let ai = AI::new(Minimax);
+        let ai = AI::new();
+        let win_board = bitboard(~[' ','o','x',
+                                   ' ','o','x',
+                                   ' ',' ',' ' ]);
+
+        let block_board = bitboard(~[' ','o','x',
+                                     ' ','o','x',
+                                     ' ','x',' ' ]);
+
+        let setup_fork_board = bitboard(~['x',' ',' ',
+                                          'o','x',' ',
+                                          ' ',' ','o' ]);
+
+        assert_eq!(Some(8), ai.minimax(win_board));
+        assert_eq!(Some(8), ai.minimax(block_board));
+        assert_eq!(Some(1), ai.minimax(setup_fork_board));
+    }
+
+    #[test]
+    fn alpha_beta_agrees_with_the_whole_algorithm() {
+        let ai = AI::new();
+        let boards = [bitboard(~[' ','o','x',
+                                 ' ','o','x',
+                                 ' ',' ',' ' ]),
+
+                      bitboard(~['x',' ',' ',
+                                 'o','x',' ',
+                                 ' ',' ','o' ]),
+
+                      Bitboard::from_board(&Board::new())];
+
+        for board in boards.iter() {
+            assert_eq!(ai.minimax(board.clone()), ai.alpha_beta(board.clone()));
+        }
+    }
+
+    #[test]
+    fn alpha_beta_picks_the_same_move_on_every_reachable_position() {
+        let ai = AI::new();
+        let positions = reachable_positions();
+
+        assert_eq!(2 * 4520, positions.len());
+
+        for board in positions.iter() {
+            let bitboard = Bitboard::from_board(board);
+
+            assert_eq!(ai.minimax(bitboard.clone()), ai.alpha_beta(bitboard));
+        }
+    }
+
+    #[test]
+    fn alpha_beta_has_no_move_on_a_full_board() {
+        let ai = AI::new();
+        let full_board = bitboard(~['o','x','o',
+                                    'o','x','x',
+                                    'x','o','x' ]);
+
+        assert_eq!(None, ai.alpha_beta(full_board));
+    }
+
+    #[test]
+    fn alpha_beta_breaks_ties_between_equally_fast_wins() {
+        let ai = AI::seeded(7);
+        let board = bitboard(~['x','o','x',
+                               'o','x','o',
+                               ' ',' ',' ' ]);
+
+        let mut picked = ~[];
+
+        do 50.times {
+            let index = ai.alpha_beta(board.clone()).unwrap();
+
+            assert!(index == 6 || index == 8);
+
+            if !picked.contains(&index) {
+                picked.push(index);
+            }
+        }
+
+        assert_eq!(2, picked.len());
+    }
+
+    #[test]
+    fn replays_the_same_moves_from_the_same_seed() {
+        let play_game = |seed: u32| {
+            let minimax = Minimax::with_ai(AI::seeded(seed));
+            let mut board = Board::new();
+            let mut moves = ~[];
+
+            while !board.is_game_over() {
+                let index = minimax.choose_move(&board).unwrap();
+
+                board = board.place(index);
+                moves.push(index);
+            }
+
+            moves
+        };
+
+        assert_eq!(play_game(7), play_game(7));
+    }
+
+    #[test]
+    fn forked_engines_leave_the_shared_numbers_alone() {
+        let ai = AI::seeded(7);
+        let same_seed = AI::seeded(7);
+
+        let forked = ai.forked();
+        same_seed.forked();
+
+        do 100.times {
+            forked.rng.gen::<u32>();
+        }
+
+        assert_eq!(same_seed.rng.gen::<u32>(), ai.rng.gen::<u32>());
+    }
+
+    #[test]
+    fn random_ties_still_play_perfectly() {
+        let ai = AI::seeded(7);
+
+        for board in reachable_positions().iter() {
+            let bitboard = Bitboard::from_board(board);
+            let index = ai.alpha_beta(bitboard.clone()).unwrap();
+            let scores = ai.get_all_scores(bitboard, 0);
+
+            assert_eq!(scores.iter().filter_map( |score: &Option<int>| *score ).max(), scores[index]);
+        }
+    }
+
+    #[test]
+    fn fills_the_transposition_table() {
+        let ai = AI::new();
+
+        ai.alpha_beta(Bitboard::from_board(&Board::new()));
+
+        assert!(ai.table.len() > 0);
+    }
+
+    #[test]
+    fn reuses_the_table_between_moves() {
+        let ai = AI::new();
+        let other_ai = ai.clone();
+        let board = Board::new().place(4);
+
+        let first_move = ai.alpha_beta(Bitboard::from_board(&board));
+        let table_size = ai.table.len();
+        let second_move = other_ai.alpha_beta(Bitboard::from_board(&board));
+
+        assert_eq!(first_move, second_move);
+        assert_eq!(table_size, other_ai.table.len());
+    }
+
+    #[test]
+    fn plays_a_larger_board() {
+        let ai = AI::new();
+        let board = Board::new_from_spaces(~['x','x','x',' ',
+                                             'o','o',' ',' ',
+                                             ' ',' ',' ',' ',
+                                             ' ',' ','o',' ' ]);
+
+        assert_eq!(Some(3), ai.alpha_beta(Bitboard::from_board(&board)));
+    }
+
+    struct Pessimist;
+
+    impl Evaluator for Pessimist {
+        fn evaluate(&self, _: &Bitboard, _: Token) -> int {
+            -1000000
+        }
+    }
+
+    fn depth_limited(max_depth: int) -> DepthLimited {
+        DepthLimited::new(max_depth, @LineEvaluator::new() as @Evaluator)
+    }
+
+    #[test]
+    fn depth_limited_search_wins_and_blocks() {
         let win_board = Board::new_from_spaces(~[' ','o','x',
                                                  ' ','o','x',
                                                  ' ',' ',' ' ]);
 
-        let block_board = Board::new_from_spaces(~[' ','o','x',
-                                                   ' ','o','x',
-                                                   ' ','x',' ' ]);
+        let block_board = Board::new_from_spaces(~['x','x',' ',' ',
+                                                   'o','o','o',' ',
+                                                   'x',' ',' ',' ',
+                                                   ' ',' ',' ',' ' ]);
+
+        assert_eq!(Some(8), depth_limited(1).choose_move(&win_board));
+        assert_eq!(Some(7), depth_limited(2).choose_move(&block_board));
+    }
+
+    #[test]
+    fn depth_limited_search_plays_gomoku_sized_boards() {
+        let board = Board::with_rules(9, 5).place(40).place(49).place(31).place(41)
+                                           .place(22).place(50).place(13);
+
+        assert_eq!(Some(4), depth_limited(2).choose_move(&board));
+    }
+
+    #[test]
+    fn depth_limited_search_plays_a_standard_gomoku_board() {
+        let board = Board::with_rules(19, 5).place(0).place(360).place(1).place(359)
+                                            .place(2).place(358).place(3).place(357);
+
+        assert_eq!(Some(4), depth_limited(1).choose_move(&board));
+    }
+
+    #[test]
+    fn depth_limited_search_agrees_with_minimax_when_it_sees_the_end() {
+        let ai = AI::new();
+        let strategy = depth_limited(9);
+
+        for board in reachable_positions().iter() {
+            let bitboard = Bitboard::from_board(board);
+
+            assert_eq!(ai.minimax(bitboard), strategy.choose_move(board));
+        }
+    }
+
+    #[test]
+    fn prefers_a_win_to_any_heuristic_score() {
+        let ai = DepthLimited::new(1, @Pessimist as @Evaluator);
+        let board = Board::new_from_spaces(~['x','x',' ',
+                                             'o','o',' ',
+                                             ' ',' ',' ' ]);
 
+        assert_eq!(Some(2), ai.choose_move(&board));
+    }
+
+    #[test]
+    fn time_limited_search_finishes_small_boards() {
+        let ai = TimeLimited::new(5000, @LineEvaluator::new() as @Evaluator);
         let setup_fork_board = Board::new_from_spaces(~['x',' ',' ',
                                                         'o','x',' ',
                                                         ' ',' ','o' ]);
 
-        assert_eq!(Some(8), ai.minimax(win_board));
-        assert_eq!(Some(8), ai.minimax(block_board));
-        assert_eq!(Some(1), ai.minimax(setup_fork_board));
+        assert_eq!(Some(1), ai.choose_move(&setup_fork_board));
+        assert_eq!(Some(0), ai.choose_move(&Board::new()));
+    }
+
+    #[test]
+    fn time_limited_search_always_has_a_move() {
+        let ai = TimeLimited::new(0, @LineEvaluator::new() as @Evaluator);
+        let board = Board::with_rules(15, 5).place(112).place(113).place(97).place(98)
+                                            .place(82).place(83).place(67).place(68);
+
+        assert_eq!(Some(52), ai.choose_move(&board));
+    }
+
+    #[test]
+    fn time_limited_search_has_no_move_on_a_full_board() {
+        let ai = TimeLimited::new(200, @LineEvaluator::new() as @Evaluator);
+        let full_board = Board::new_from_spaces(~['o','x','o',
+                                                  'o','x','x',
+                                                  'x','o','x' ]);
+
+        assert_eq!(None, ai.choose_move(&full_board));
+    }
+
+    #[test]
+    fn plays_the_lowest_available_space() {
+        let board = Board::new_from_spaces(~['x','o',' ',
+                                             ' ',' ',' ',
+                                             ' ',' ',' ' ]);
+
+        assert_eq!(Some(2), LowestAvailable.choose_move(&board));
+        assert_eq!(~"Lowest available", LowestAvailable.name());
+    }
+
+    #[test]
+    fn random_moves_are_always_available() {
+        let ai = RandomAvailable::new();
+        let board = Board::new_from_spaces(~['x','o','x',
+                                             ' ','o',' ',
+                                             'o','x',' ' ]);
+
+        do 50.times {
+            let index = ai.choose_move(&board).unwrap();
+
+            assert!(board.available_spaces().contains(&index));
+        }
+
+        assert_eq!(None, ai.choose_move(&Board::new_from_spaces(~['o','x','o',
+                                                                 'o','x','x',
+                                                                 'x','o','x' ])));
+    }
+
+    #[test]
+    fn careless_players_take_a_win_before_a_block() {
+        let ai = Careless::new(1.0);
+        let board = Board::new_from_spaces(~['x','x',' ',
+                                             'o','o',' ',
+                                             ' ',' ',' ' ]);
+
+        assert_eq!(Some(2), ai.choose_move(&board));
+    }
+
+    #[test]
+    fn careless_players_block_as_often_as_they_are_told() {
+        let always_blocks = Careless::new(1.0);
+        let never_blocks = Careless::new(0.0);
+        let board = Board::new_from_spaces(~['x',' ',' ',
+                                             'o','o',' ',
+                                             'x',' ',' ' ]);
+
+        let mut missed_blocks = 0;
+
+        do 50.times {
+            assert_eq!(Some(5), always_blocks.choose_move(&board));
+
+            if never_blocks.choose_move(&board) != Some(5) {
+                missed_blocks += 1;
+            }
+        }
+
+        assert!(missed_blocks > 0);
+    }
+
+    #[test]
+    fn fallible_players_play_perfectly_when_they_never_err() {
+        let ai = Fallible::new(0.0);
+        let setup_fork_board = Board::new_from_spaces(~['x',' ',' ',
+                                                        'o','x',' ',
+                                                        ' ',' ','o' ]);
+
+        assert_eq!(Some(1), ai.choose_move(&setup_fork_board));
+    }
+
+    #[test]
+    fn monte_carlo_search_finds_a_win() {
+        let ai = MonteCarlo::new(Iterations(1000), DEFAULT_EXPLORATION);
+        let win_board = Board::new_from_spaces(~[' ','o','x',
+                                                 ' ','o','x',
+                                                 ' ',' ',' ' ]);
+
+        assert_eq!(Some(8), ai.choose_move(&win_board));
+    }
+
+    #[test]
+    fn rule_based_players_follow_the_rules() {
+        let ai = RuleBased;
+
+        assert_eq!(Some(4), ai.choose_move(&Board::new()));
+        assert_eq!(Some(~"Take the center."), ai.explain(&Board::new()));
+    }
+
+    #[test]
+    fn learned_players_play_their_best_valued_move() {
+        let mut table = ValueTable::new();
+        table.update(&Board::new().place(4), 1.0, 0.5);
+
+        let ai = Learned::new(@table);
+
+        assert_eq!(Some(4), ai.choose_move(&Board::new()));
+    }
+
+    // The beads in each box along the path x played for two turns, before
+    // and after Menace hears the outcome.
+    fn menace_beads_along_the_path(outcome: GameOutcome) -> (~[uint], ~[uint]) {
+        let matchboxes = @mut Matchboxes::new();
+        let ai = Menace::new(matchboxes);
+
+        let board = Board::new().place(ai.choose_move(&Board::new()).unwrap());
+        let board = board.place(LowestAvailable.choose_move(&board).unwrap());
+        ai.choose_move(&board);
+
+        let path = matchboxes.history.clone();
+        let counts = || path.map( |&(_, ref key, index): &(Token, ~str, int)| matchboxes.boxes.get(key)[index] );
+        let before = counts();
+
+        assert!(ai.game_over(&outcome, X).is_ok());
+
+        assert!(matchboxes.history.is_empty());
+        (before, counts())
+    }
+
+    #[test]
+    fn menace_learns_when_the_game_is_over() {
+        assert_eq!((~[4, 3], ~[7, 6]), menace_beads_along_the_path(Win { token: X, line: ~[] }));
+        assert_eq!((~[4, 3], ~[3, 2]), menace_beads_along_the_path(Win { token: O, line: ~[] }));
+        assert_eq!((~[4, 3], ~[5, 4]), menace_beads_along_the_path(Draw));
     }
 }
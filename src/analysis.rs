@@ -0,0 +1,276 @@
+use ai::*;
+use bitboard::*;
+use board::*;
+use transposition::*;
+
+// What a move is worth with perfect play from both sides. Wins and losses
+// count the moves until the game ends, including this one. A search that
+// stops short of the end may find neither, and then the value is unknown.
+#[deriving(Clone, Eq)]
+pub enum MoveValue {
+    WinIn(uint),
+    Drawn,
+    Unknown,
+    LossIn(uint)
+}
+
+impl MoveValue {
+    // Higher is better: quicker wins, then draws, then moves nothing is known
+    // about, then slower losses.
+    pub fn rank(&self) -> int {
+        match *self {
+            WinIn(moves)  => INFINITY - moves as int,
+            Drawn         => 0,
+            Unknown       => -1,
+            LossIn(moves) => moves as int - INFINITY
+        }
+    }
+}
+
+#[deriving(Clone, Eq)]
+pub struct MoveAnalysis {
+    index: int,
+    value: MoveValue,
+    principal_variation: ~[int]
+}
+
+impl AI {
+    // Every legal move, in board order, with its value and the line of
+    // best play that follows it, searched to the end of the game.
+    pub fn analyze(&self, board: &Board) -> ~[MoveAnalysis] {
+        self.analyze_with(board, &mut Search::new(EndOfGame))
+    }
+
+    // Each move is searched with a full window, so its score is exact. Once
+    // the deadline passes, only the moves analyzed by then are returned.
+    pub fn analyze_with(&self, board: &Board, search: &mut Search) -> ~[MoveAnalysis] {
+        let bitboard = Bitboard::from_board(board);
+        let mut analyses = ~[];
+
+        if bitboard.is_game_over() {
+            return analyses;
+        }
+
+        let sees_the_end = search.horizon.remaining(0) >= bitboard.available_spaces().len() as int;
+
+        for &index in bitboard.available_spaces().iter() {
+            let score = self.alpha_beta_score_move(index, bitboard.clone(), 0, -INFINITY, INFINITY, search);
+            let line = ~[index] + self.principal_variation(bitboard.place(index), 1, -score, search);
+
+            if search.timed_out {
+                break;
+            }
+
+            analyses.push(MoveAnalysis { index: index,
+                                         value: value_of_score(&bitboard, score, sees_the_end),
+                                         principal_variation: line });
+        }
+
+        analyses
+    }
+
+    pub fn best_analysis(&self, board: &Board) -> Option<MoveAnalysis> {
+        self.best_analysis_with(board, &mut Search::new(EndOfGame))
+    }
+
+    // Nothing is recommended when the deadline passes before every move has
+    // been analyzed, since the best move may be among those left out.
+    pub fn best_analysis_with(&self, board: &Board, search: &mut Search) -> Option<MoveAnalysis> {
+        let analyses = self.analyze_with(board, search);
+        let mut best: Option<MoveAnalysis> = None;
+
+        if search.timed_out {
+            return None;
+        }
+
+        for analysis in analyses.move_iter() {
+            let is_better = match best {
+                Some(ref best) => analysis.value.rank() > best.value.rank(),
+                None           => true
+            };
+
+            if is_better {
+                best = Some(analysis);
+            }
+        }
+
+        best
+    }
+
+    // Follows the moves that keep a position's score, checking each with a
+    // null window that the table answers from the search just done. The
+    // line stops at the end of the game or at the horizon.
+    fn principal_variation(&self, board: Bitboard, depth: int, score: int, search: &mut Search) -> ~[int] {
+        let mut line = ~[];
+        let mut board = board;
+        let mut depth = depth;
+        let mut score = score;
+
+        while !board.is_game_over() && search.horizon.remaining(depth) > 0 && !search.timed_out {
+            let best_index = board.available_spaces().move_iter().find( |&index: &int|
+                self.alpha_beta_score_move(index, board.clone(), depth, score - 1, score, search) >= score
+            );
+
+            match best_index {
+                Some(index) => {
+                    line.push(index);
+                    board = board.place(index);
+                    depth += 1;
+                    score = -score;
+                },
+
+                None => break
+            }
+        }
+
+        line
+    }
+}
+
+// Wins and losses score less the later they finish, which gives how many
+// moves away they are. Anything smaller is a draw when the search saw every
+// line to the end, and otherwise may be a guess at a position past the horizon.
+fn value_of_score(board: &Bitboard, score: int, sees_the_end: bool) -> MoveValue {
+    let moves_until = |score: int| (board.len() as int - score / WIN_SCALE + 1) as uint;
+
+    if score >= WIN_SCALE {
+        WinIn(moves_until(score))
+    } else if score <= -WIN_SCALE {
+        LossIn(moves_until(-score))
+    } else if sees_the_end {
+        Drawn
+    } else {
+        Unknown
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ai::*;
+    use board::*;
+    use evaluator::*;
+    use extra::time::precise_time_ns;
+    use strategy::*;
+
+    fn analysis_of(analyses: &[MoveAnalysis], index: int) -> MoveAnalysis {
+        analyses.iter().find( |analysis: &&MoveAnalysis| analysis.index == index ).unwrap().clone()
+    }
+
+    #[test]
+    fn analyzes_every_legal_move() {
+        let ai = AI::new();
+        let board = Board::new_from_spaces(~['x','o','x',
+                                             ' ','o',' ',
+                                             ' ',' ',' ' ]);
+
+        let indexes = ai.analyze(&board).map( |analysis: &MoveAnalysis| analysis.index );
+
+        assert_eq!(~[3, 5, 6, 7, 8], indexes);
+    }
+
+    #[test]
+    fn values_wins_losses_and_draws() {
+        let ai = AI::new();
+        let board = Board::new_from_spaces(~[' ','o','x',
+                                             ' ','o','x',
+                                             ' ',' ',' ' ]);
+
+        let analyses = ai.analyze(&board);
+
+        assert_eq!(WinIn(1), analysis_of(analyses, 8).value);
+        assert_eq!(LossIn(2), analysis_of(analyses, 0).value);
+        assert_eq!(Drawn, AI::new().analyze(&Board::new())[0].value);
+    }
+
+    #[test]
+    fn follows_each_move_with_the_best_replies() {
+        let ai = AI::new();
+        let board = Board::new_from_spaces(~[' ','o','x',
+                                             ' ','o','x',
+                                             ' ',' ',' ' ]);
+
+        let analyses = ai.analyze(&board);
+
+        assert_eq!(~[8], analysis_of(analyses, 8).principal_variation);
+        assert_eq!(~[0, 7], analysis_of(analyses, 0).principal_variation);
+        assert_eq!(9, ai.analyze(&Board::new())[0].principal_variation.len());
+    }
+
+    #[test]
+    fn stops_each_line_at_the_horizon() {
+        let ai = AI::new();
+        let mut search = Search::new(Ply(2, @LineEvaluator::new() as @Evaluator));
+
+        let analyses = ai.analyze_with(&Board::with_size(4), &mut search);
+
+        assert_eq!(16, analyses.len());
+        assert!(analyses.iter().all( |analysis: &MoveAnalysis| analysis.principal_variation.len() == 2 ));
+        assert!(analyses.iter().all( |analysis: &MoveAnalysis| analysis.value == Unknown ));
+    }
+
+    #[test]
+    fn returns_only_the_moves_analyzed_before_the_deadline() {
+        let ai = AI::new();
+        let mut search = Search::with_deadline(EndOfGame, precise_time_ns());
+
+        assert_eq!(~[], ai.analyze_with(&Board::with_size(4), &mut search));
+        assert!(search.timed_out);
+    }
+
+    #[test]
+    fn recommends_nothing_when_the_deadline_passes() {
+        let ai = AI::new();
+        let mut search = Search::with_deadline(EndOfGame, precise_time_ns());
+
+        assert_eq!(None, ai.best_analysis_with(&Board::with_size(4), &mut search));
+    }
+
+    #[test]
+    fn values_a_draw_it_sees_to_the_end_as_drawn() {
+        let ai = AI::new();
+        let mut search = Search::new(Ply(9, @LineEvaluator::new() as @Evaluator));
+
+        assert_eq!(Drawn, ai.analyze_with(&Board::new(), &mut search)[4].value);
+    }
+
+    #[test]
+    fn ranks_quicker_wins_and_slower_losses_higher() {
+        assert!(WinIn(1).rank() > WinIn(3).rank());
+        assert!(WinIn(9).rank() > Drawn.rank());
+        assert!(Drawn.rank() > Unknown.rank());
+        assert!(Unknown.rank() > LossIn(8).rank());
+        assert!(LossIn(8).rank() > LossIn(2).rank());
+    }
+
+    #[test]
+    fn has_nothing_to_analyze_when_the_game_is_over() {
+        let ai = AI::new();
+        let board = Board::new_from_spaces(~['x','x','x',
+                                             'o','o',' ',
+                                             ' ',' ',' ' ]);
+
+        assert_eq!(~[], ai.analyze(&board));
+        assert_eq!(None, ai.best_analysis(&board));
+    }
+
+    #[test]
+    fn best_analysis_agrees_with_minimax() {
+        let ai = AI::new();
+        let boards = [Board::new_from_spaces(~['x',' ',' ',
+                                               'o','x',' ',
+                                               ' ',' ','o' ]),
+
+                      Board::new_from_spaces(~[' ','o','x',
+                                               ' ','o','x',
+                                               ' ','x',' ' ]),
+
+                      Board::new()];
+
+        for board in boards.iter() {
+            let best_index = ai.best_analysis(board).map( |analysis: &MoveAnalysis| analysis.index );
+
+            assert_eq!(Minimax::new().choose_move(board), best_index);
+        }
+    }
+}
@@ -0,0 +1,525 @@
+use std::rand::{Rng, XorShiftRng};
+
+use board::*;
+use symmetry::*;
+
+// One bit per space, in as many words as the highest space set needs, so
+// boards of any size fit. Missing words count as all clear.
+pub struct Bits {
+    words: ~[u64]
+}
+
+impl Bits {
+    pub fn empty() -> Bits {
+        Bits { words: ~[] }
+    }
+
+    pub fn with(&self, index: uint) -> Bits {
+        let mut words = self.words.clone();
+
+        while words.len() <= index / 64 {
+            words.push(0);
+        }
+
+        words[index / 64] |= bit(index);
+
+        Bits { words: words }
+    }
+
+    pub fn contains(&self, index: uint) -> bool {
+        self.word(index / 64) & bit(index) != 0
+    }
+
+    pub fn contains_all(&self, other: &Bits) -> bool {
+        range(0u, other.words.len()).all( |i: uint| self.word(i) & other.words[i] == other.words[i] )
+    }
+
+    pub fn intersection(&self, other: &Bits) -> Bits {
+        let words = range(0u, self.words.len().min(&other.words.len())).map( |i: uint|
+            self.words[i] & other.words[i]
+        ).collect();
+
+        Bits { words: words }
+    }
+
+    pub fn count(&self) -> uint {
+        let mut count = 0;
+
+        for &word in self.words.iter() {
+            let mut remaining = word;
+
+            while remaining != 0 {
+                remaining &= remaining - 1;
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    fn word(&self, i: uint) -> u64 {
+        if i < self.words.len() { self.words[i] } else { 0 }
+    }
+}
+
+impl Clone for Bits {
+    fn clone(&self) -> Bits {
+        Bits { words: self.words.clone() }
+    }
+}
+
+impl Eq for Bits {
+    fn eq(&self, other: &Bits) -> bool {
+        let words = self.words.len().max(&other.words.len());
+
+        range(0u, words).all( |i: uint| self.word(i) == other.word(i) )
+    }
+}
+
+fn bit(index: uint) -> u64 {
+    1u64 << ((index % 64) as u64)
+}
+
+// Shared by every Bitboard derived from the same Board, so placing a token
+// never copies the win masks or hash keys.
+struct Geometry {
+    spaces: uint,
+    dimension: uint,
+    win_length: uint,
+    win_masks: ~[Bits],
+    masks_by_space: ~[~[uint]],
+    space_keys: ~[(u64, u64)],
+    side_key: u64,
+    symmetries: ~[~[int]]
+}
+
+impl Geometry {
+    fn new(board: &Board) -> Geometry {
+        let mut win_masks = ~[];
+        let mut masks_by_space = ~[];
+        let mut space_keys = ~[];
+        let mut symmetries = ~[];
+
+        // Seeding from the rules keeps hashes of different board sizes and
+        // win lengths apart when they share a table.
+        let mut rng = XorShiftRng::new_seeded(board.spaces.len() as u32, board.win_length as u32,
+                                              0x9e3779b9, 0x85ebca6b);
+
+        do board.spaces.len().times {
+            space_keys.push((rng.gen::<u64>(), rng.gen::<u64>()));
+        }
+
+        // Win lines map onto win lines under every rotation and reflection,
+        // so symmetric positions always have the same value.
+        for transform in Transform::all().iter() {
+            let images = range(0, board.spaces.len() as int).map( |index: int|
+                transform.apply(index, board.dimension())
+            ).collect::<~[int]>();
+
+            symmetries.push(images);
+        }
+
+        do board.spaces.len().times {
+            masks_by_space.push(~[]);
+        }
+
+        for line in board.lines().iter() {
+            let mut mask = Bits::empty();
+
+            for &index in line.iter() {
+                mask = mask.with(index as uint);
+                masks_by_space[index].push(win_masks.len());
+            }
+
+            win_masks.push(mask);
+        }
+
+        Geometry { spaces: board.spaces.len(),
+                   dimension: board.dimension(),
+                   win_length: board.win_length,
+                   win_masks: win_masks,
+                   masks_by_space: masks_by_space,
+                   space_keys: space_keys,
+                   side_key: rng.gen::<u64>(),
+                   symmetries: symmetries }
+    }
+
+    fn space_key(&self, index: int, token: Token) -> u64 {
+        let (x_key, o_key) = self.space_keys[index];
+
+        match token {
+            X => x_key,
+            O => o_key
+        }
+    }
+}
+
+// The position's Zobrist hash as seen through each of the board's eight
+// symmetries. The smallest of them names the position and all its images.
+struct SymmetricHash {
+    keys: [u64, ..8]
+}
+
+impl SymmetricHash {
+    fn empty() -> SymmetricHash {
+        SymmetricHash { keys: [0u64, ..8] }
+    }
+
+    fn toggle_space(&self, index: int, token: Token, geometry: &Geometry) -> SymmetricHash {
+        let mut keys = self.keys;
+
+        for i in range(0u, 8) {
+            keys[i] ^= geometry.space_key(geometry.symmetries[i][index], token);
+        }
+
+        SymmetricHash { keys: keys }
+    }
+
+    fn toggle_side(&self, geometry: &Geometry) -> SymmetricHash {
+        let mut keys = self.keys;
+
+        for i in range(0u, 8) {
+            keys[i] ^= geometry.side_key;
+        }
+
+        SymmetricHash { keys: keys }
+    }
+
+    fn smallest(&self) -> u64 {
+        *self.keys.iter().min().unwrap()
+    }
+}
+
+impl Clone for SymmetricHash {
+    fn clone(&self) -> SymmetricHash {
+        SymmetricHash { keys: self.keys }
+    }
+}
+
+// The search's view of a Board: one mask per player and precomputed win
+// masks. Board itself still keeps its cells, since display, notation,
+// validation and the rules all index them directly. Searches convert once
+// at the root and then only ever place tokens here, so the cell vector is
+// never copied inside a search.
+#[deriving(Clone)]
+pub struct Bitboard {
+    x: Bits,
+    o: Bits,
+    to_move: Token,
+    winner: Option<Token>,
+    hash: SymmetricHash,
+    geometry: @Geometry
+}
+
+impl Bitboard {
+    pub fn from_board(board: &Board) -> Bitboard {
+        let geometry = @Geometry::new(board);
+        let mut x = Bits::empty();
+        let mut o = Bits::empty();
+        let mut hash = SymmetricHash::empty();
+
+        for (index, &space) in board.spaces.iter().enumerate() {
+            match space {
+                Filled(X) => x = x.with(index),
+                Filled(O) => o = o.with(index),
+                Empty     => ()
+            }
+
+            match space.token() {
+                Some(token) => hash = hash.toggle_space(index as int, token, geometry),
+                None        => ()
+            }
+        }
+
+        if board.current_token() == O {
+            hash = hash.toggle_side(geometry);
+        }
+
+        Bitboard { x: x,
+                   o: o,
+                   to_move: board.current_token(),
+                   winner: board.winner(),
+                   hash: hash,
+                   geometry: geometry }
+    }
+
+    pub fn len(&self) -> uint {
+        self.geometry.spaces
+    }
+
+    pub fn dimension(&self) -> uint {
+        self.geometry.dimension
+    }
+
+    pub fn win_length(&self) -> uint {
+        self.geometry.win_length
+    }
+
+    pub fn space(&self, index: int) -> Cell {
+        let index = index as uint;
+
+        if self.x.contains(index) {
+            Filled(X)
+        } else if self.o.contains(index) {
+            Filled(O)
+        } else {
+            Empty
+        }
+    }
+
+    pub fn place(&self, index: int) -> Bitboard {
+        let mut new_board = self.clone();
+
+        if self.space(index) != Empty {
+            return new_board;
+        }
+
+        let token = self.current_token();
+
+        if token == X {
+            new_board.x = self.x.with(index as uint);
+        } else {
+            new_board.o = self.o.with(index as uint);
+        }
+
+        new_board.to_move = token.other();
+        new_board.hash = self.hash.toggle_space(index, token, self.geometry).toggle_side(self.geometry);
+
+        if new_board.winner.is_none() && new_board.completes_line(index, token) {
+            new_board.winner = Some(token);
+        }
+
+        new_board
+    }
+
+    fn completes_line(&self, index: int, token: Token) -> bool {
+        let tokens = match token { X => &self.x, O => &self.o };
+
+        self.geometry.masks_by_space[index].iter().any( |&mask: &uint|
+            tokens.contains_all(&self.geometry.win_masks[mask])
+        )
+    }
+
+    fn filled_spaces(&self) -> uint {
+        self.x.count() + self.o.count()
+    }
+
+    pub fn current_token(&self) -> Token {
+        self.to_move
+    }
+
+    pub fn winner(&self) -> Option<Token> {
+        self.winner
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.winner.is_some() || self.filled_spaces() == self.len()
+    }
+
+    // Equal for positions that are rotations or reflections of each other.
+    pub fn hash(&self) -> u64 {
+        self.hash.smallest()
+    }
+
+    pub fn available_spaces(&self) -> ~[int] {
+        range(0, self.len() as int).filter( |&index: &int| self.space(index) == Empty ).collect()
+    }
+
+    // How many x and o tokens lie on each winning line.
+    pub fn line_counts(&self) -> ~[(uint, uint)] {
+        self.geometry.win_masks.iter().map( |mask: &Bits|
+            (self.x.intersection(mask).count(), self.o.intersection(mask).count())
+        ).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use board::*;
+    use std::vec::from_elem;
+
+    #[test]
+    fn sets_and_counts_bits_across_words() {
+        let bits = Bits::empty().with(3).with(64).with(200);
+
+        assert!(bits.contains(64));
+        assert!(bits.contains(200));
+        assert!(!bits.contains(65));
+        assert_eq!(3, bits.count());
+        assert!(bits.contains_all(&Bits::empty().with(3).with(200)));
+        assert!(!bits.contains_all(&Bits::empty().with(4)));
+        assert_eq!(Bits::empty().with(200), bits.intersection(&Bits::empty().with(4).with(200)));
+    }
+
+    #[test]
+    fn mirrors_the_board_it_was_built_from() {
+        let board = Board::new_from_spaces(~['x','o',' ',
+                                             ' ','x',' ',
+                                             ' ','o',' ' ]);
+        let bitboard = Bitboard::from_board(&board);
+
+        assert_eq!(Filled(X), bitboard.space(0));
+        assert_eq!(Filled(O), bitboard.space(1));
+        assert_eq!(Empty, bitboard.space(2));
+        assert_eq!(board.available_spaces(), bitboard.available_spaces());
+        assert_eq!(board.current_token(), bitboard.current_token());
+    }
+
+    #[test]
+    fn plays_the_same_game_as_the_board() {
+        let moves = [4, 0, 8, 2, 1, 7, 6, 3, 5];
+        let mut board = Board::new();
+        let mut bitboard = Bitboard::from_board(&board);
+
+        for &index in moves.iter() {
+            board = board.place(index);
+            bitboard = bitboard.place(index);
+
+            assert_eq!(board.winner(), bitboard.winner());
+            assert_eq!(board.is_game_over(), bitboard.is_game_over());
+            assert_eq!(board.available_spaces(), bitboard.available_spaces());
+            assert_eq!(board.current_token(), bitboard.current_token());
+        }
+    }
+
+    #[test]
+    fn can_only_place_a_token_in_an_empty_space() {
+        let bitboard = Bitboard::from_board(&Board::new()).place(0).place(0);
+
+        assert_eq!(Filled(X), bitboard.space(0));
+        assert_eq!(O, bitboard.current_token());
+    }
+
+    #[test]
+    fn keeps_the_side_to_move_of_the_board() {
+        let bitboard = Bitboard::from_board(&Board::new().with_to_move(O));
+        assert_eq!(O, bitboard.current_token());
+
+        let after_move = bitboard.place(4);
+        assert_eq!(Filled(O), after_move.space(4));
+        assert_eq!(X, after_move.current_token());
+    }
+
+    #[test]
+    fn hashes_symmetric_positions_alike() {
+        let corner = Bitboard::from_board(&Board::new_from_spaces(~['x','o',' ',
+                                                                    ' ',' ',' ',
+                                                                    ' ',' ',' ' ]));
+
+        let other_corner = Bitboard::from_board(&Board::new_from_spaces(~[' ',' ',' ',
+                                                                          ' ',' ','o',
+                                                                          ' ',' ','x' ]));
+
+        let different = Bitboard::from_board(&Board::new_from_spaces(~['x',' ',' ',
+                                                                       ' ','o',' ',
+                                                                       ' ',' ',' ' ]));
+
+        assert_eq!(corner.hash(), other_corner.hash());
+        assert!(corner.hash() != different.hash());
+    }
+
+    #[test]
+    fn hashes_the_side_to_move() {
+        let board = Board::new_from_spaces(~['x','o',' ',
+                                             ' ',' ',' ',
+                                             ' ',' ',' ' ]);
+
+        let x_to_move = Bitboard::from_board(&board);
+        let o_to_move = Bitboard::from_board(&board.with_to_move(O));
+
+        assert!(x_to_move.hash() != o_to_move.hash());
+    }
+
+    #[test]
+    fn updates_the_hash_as_tokens_are_placed() {
+        let moves = [4, 0, 8, 2];
+        let mut board = Board::new();
+        let mut bitboard = Bitboard::from_board(&board);
+
+        for &index in moves.iter() {
+            board = board.place(index);
+            bitboard = bitboard.place(index);
+
+            assert_eq!(Bitboard::from_board(&board).hash(), bitboard.hash());
+        }
+    }
+
+    #[test]
+    fn hashes_different_rules_apart() {
+        let three_in_a_row = Bitboard::from_board(&Board::with_rules(4, 3));
+        let four_in_a_row = Bitboard::from_board(&Board::with_rules(4, 4));
+
+        assert!(three_in_a_row.place(5).hash() != four_in_a_row.place(5).hash());
+    }
+
+    #[test]
+    fn knows_someone_wins() {
+        let x_wins = Bitboard::from_board(&Board::new_from_spaces(~['o','x','o',
+                                                                    'o','x','x',
+                                                                    ' ',' ',' ' ])).place(7);
+
+        let o_wins = Bitboard::from_board(&Board::new_from_spaces(~['o','o','o',
+                                                                    'x','x',' ',
+                                                                    'x',' ',' ' ]));
+
+        assert_eq!(Some(X), x_wins.winner());
+        assert!(x_wins.is_game_over());
+        assert_eq!(Some(O), o_wins.winner());
+    }
+
+    #[test]
+    fn handles_boards_larger_than_one_word() {
+        let mut spaces = from_elem(225, ' ');
+        spaces[200] = 'x';
+        spaces[201] = 'x';
+        spaces[202] = 'x';
+        spaces[203] = 'x';
+        spaces[30] = 'o';
+        spaces[31] = 'o';
+        spaces[32] = 'o';
+        spaces[33] = 'o';
+
+        let board = Board::new_from_spaces(spaces).with_win_length(5);
+        let bitboard = Bitboard::from_board(&board);
+
+        assert_eq!(217, bitboard.available_spaces().len());
+        assert_eq!(Some(X), bitboard.place(204).winner());
+        assert_eq!(None, bitboard.place(100).winner());
+        assert_eq!(Some(O), bitboard.place(100).place(34).winner());
+    }
+
+    #[test]
+    fn handles_a_gomoku_board() {
+        let mut board = Board::with_rules(19, 5);
+
+        for &index in [360, 0, 359, 1, 358, 2, 357, 3].iter() {
+            board = board.place(index);
+        }
+
+        let bitboard = Bitboard::from_board(&board);
+
+        assert_eq!(353, bitboard.available_spaces().len());
+        assert_eq!(Some(X), bitboard.place(356).winner());
+        assert_eq!(Some(O), bitboard.place(100).place(4).winner());
+    }
+
+    #[test]
+    fn compares_bits_whatever_words_they_use() {
+        assert_eq!(Bits::empty(), Bits::empty().with(300).intersection(&Bits::empty().with(3)));
+        assert!(Bits::empty().with(3).contains_all(&Bits::empty()));
+        assert!(!Bits::empty().with(3).contains(300));
+    }
+
+    #[test]
+    fn counts_the_tokens_on_each_line() {
+        let bitboard = Bitboard::from_board(&Board::new_from_spaces(~['x','x',' ',
+                                                                      ' ','o',' ',
+                                                                      ' ',' ',' ' ]));
+        let counts = bitboard.line_counts();
+
+        assert_eq!(8, counts.len());
+        assert_eq!(1, counts.iter().count( |&counts: &(uint, uint)| counts == (2, 0) ));
+        assert_eq!(4, counts.iter().count( |&(_, o): &(uint, uint)| o == 1 ));
+    }
+}
@@ -1,78 +1,149 @@
 use std::iter::Repeat;
 use std::num::sqrt;
 
+use symbols::*;
+
+#[deriving(Clone, Eq)]
+pub enum Token {
+    X,
+    O
+}
+
+impl Token {
+    pub fn other(&self) -> Token {
+        match *self {
+            X => O,
+            O => X
+        }
+    }
+}
+
+#[deriving(Clone, Eq)]
+pub enum Cell {
+    Empty,
+    Filled(Token)
+}
+
+impl Cell {
+    pub fn token(&self) -> Option<Token> {
+        match *self {
+            Empty         => None,
+            Filled(token) => Some(token)
+        }
+    }
+}
+
+#[deriving(Clone, Eq)]
+pub enum MoveError {
+    OutOfBounds,
+    Occupied,
+    GameAlreadyOver
+}
+
+#[deriving(Clone, Eq)]
+pub enum GameOutcome {
+    InProgress,
+    Win { token: Token, line: ~[int] },
+    Draw
+}
+
 #[deriving(Clone, Eq)]
 struct Board {
-    spaces: ~[char],
+    spaces: ~[Cell],
+    win_length: uint,
+    to_move: Token,
     flash_message: Option<~str>
 }
 
 impl Board {
     pub fn new() -> Board {
-        let empty_spaces: ~[char] = Repeat::new(' ').take(9).collect::<~[char]>();
+        Board::with_size(3)
+    }
+
+    pub fn with_size(dimension: uint) -> Board {
+        Board::with_rules(dimension, dimension)
+    }
+
+    pub fn with_rules(dimension: uint, win_length: uint) -> Board {
+        let size = dimension * dimension;
+        let empty_spaces: ~[Cell] = Repeat::new(Empty).take(size).collect::<~[Cell]>();
 
         Board { spaces: empty_spaces,
+                win_length: win_length,
+                to_move: X,
                 flash_message: None }
     }
 
     pub fn new_from_spaces(spaces: ~[char]) -> Board {
+        match Symbols::default().chars_to_cells(spaces) {
+            Some(cells) => Board::new_from_cells(cells),
+            None        => fail!("Board spaces may only contain 'x', 'o' or ' '.")
+        }
+    }
+
+    pub fn new_from_cells(spaces: ~[Cell]) -> Board {
+        let dimension = sqrt(spaces.len() as float) as uint;
+        let x_count = spaces.iter().count( |cell: &Cell| *cell == Filled(X) );
+        let o_count = spaces.iter().count( |cell: &Cell| *cell == Filled(O) );
+        let to_move = if o_count < x_count { O } else { X };
+
         Board { spaces: spaces,
+                win_length: dimension,
+                to_move: to_move,
                 flash_message: None }
     }
 
+    pub fn with_win_length(&self, win_length: uint) -> Board {
+        Board { spaces: self.spaces.clone(),
+                win_length: win_length,
+                to_move: self.to_move,
+                flash_message: self.flash_message.clone() }
+    }
+
+    pub fn with_to_move(&self, to_move: Token) -> Board {
+        Board { spaces: self.spaces.clone(),
+                win_length: self.win_length,
+                to_move: to_move,
+                flash_message: self.flash_message.clone() }
+    }
+
+    // Copies the cells, so searches place moves on a Bitboard instead.
     pub fn place(&self, index: int) -> Board {
         let mut new_spaces = self.spaces.clone();
+        let mut to_move = self.to_move;
 
-        if new_spaces[index] == ' ' {
-            new_spaces[index] = self.current_token();
+        if new_spaces[index] == Empty {
+            new_spaces[index] = Filled(self.to_move);
+            to_move = self.to_move.other();
         }
 
         Board { spaces: new_spaces,
+                win_length: self.win_length,
+                to_move: to_move,
                 flash_message: None }
     }
 
-    pub fn try_move(&self, index: int) -> Board {
-        let error_message = self.get_error_message(index);
-
-        match error_message {
-            Some(*) => Board { spaces: self.spaces.clone(),
-                               flash_message: error_message },
-
-            None => self.check_game_over(index)
-        }
+    pub fn with_flash_message(&self, flash_message: Option<~str>) -> Board {
+        Board { spaces: self.spaces.clone(),
+                win_length: self.win_length,
+                to_move: self.to_move,
+                flash_message: flash_message }
     }
 
-    fn get_error_message(&self, index: int) -> Option<~str> {
-        //println("get_error_message in board.  index = " + index.to_str() + "\n");
-        match index {
-            0..8 => self.check_against_available_moves(index),
-            _    => Some(~"Please choose a number from 0 to 8.")
+    pub fn try_move(&self, index: int) -> Result<Board, MoveError> {
+        match self.check_move(index) {
+            Some(error) => Err(error),
+            None        => Ok(self.place(index))
         }
     }
 
-    fn check_against_available_moves(&self, index: int) -> Option<~str> {
-        if self.available_spaces().contains(@index) {
-            None
-        } else {
-            Some(~"That space is already taken.")
-        }
-    }
-
-    fn check_game_over(&self, index: int) -> Board {
-        let new_board = self.place(index);
-
-        let flash_message = match new_board.winner() {
-            Some(winner) => Some(winner.to_str() + " wins!"),
-            None         => new_board.check_tie_game()
-        };
-
-        Board { spaces: new_board.spaces,
-                flash_message: flash_message }
-    }
-
-    fn check_tie_game(&self) -> Option<~str> {
-        if self.board_is_full() {
-            Some(~"Tie game!")
+    fn check_move(&self, index: int) -> Option<MoveError> {
+        if self.is_game_over() {
+            Some(GameAlreadyOver)
+        } else if index < 0 || index >= self.spaces.len() as int {
+            Some(OutOfBounds)
+        } else if !self.available_spaces().contains(&index) {
+            Some(Occupied)
         } else {
             None
         }
@@ -80,17 +151,13 @@ impl Board {
 
     fn empty_spaces(&self) -> uint {
         let mut empty_spaces = self.spaces.clone().to_owned();
-        empty_spaces.retain( |x: &char| *x == ' ' );
+        empty_spaces.retain( |x: &Cell| *x == Empty );
 
         empty_spaces.len()
     }
 
-    pub fn current_token(&self) -> char {
-        if self.empty_spaces().is_odd() {
-            'x'
-        } else {
-            'o'
-        }
+    pub fn current_token(&self) -> Token {
+        self.to_move
     }
 
     fn transpose(&self) -> Board {
@@ -98,91 +165,83 @@ impl Board {
         let mut new_spaces = ~[];
         let mut i: uint = 0;
         let dim = self.dimension();
-        let size = dim * dim;
 
-        do (size - 1).times {
-            new_spaces.push(orig_spaces[(i * dim) % (size - 1)]);
+        do orig_spaces.len().times {
+            new_spaces.push(orig_spaces[(i % dim) * dim + i / dim]);
             i += 1;
         }
 
-        new_spaces.push(orig_spaces[8]);
-
         Board { spaces: new_spaces,
+                win_length: self.win_length,
+                to_move: self.to_move,
                 flash_message: None }
     }
 
-    fn is_winning(&self, line: &[char]) -> bool {
-        self.is_all_same_token(line.to_owned())
-    }
-
-    fn dimension(&self) -> uint {
+    pub fn dimension(&self) -> uint {
         let size = self.spaces.len() as float;
         sqrt(size) as uint
     }
 
-    fn has_backslash_diagonal_winner(&self) -> bool{
-        let mut i = 0;
-        let dim = self.dimension();
-        let mut diagonal_tokens: ~[char] = ~[];
+    fn is_all_same_token(&self, cells: ~[Cell]) -> bool {
+        let first_cell = cells[0];
 
-        do dim.times {
-            diagonal_tokens.push(self.spaces[(dim + 1) * i]);
-            i += 1;
-        }
-
-        self.is_all_same_token(diagonal_tokens)
+        cells.iter().all( |x: &Cell| *x == first_cell) && first_cell != Empty
     }
 
-    fn has_slash_diagonal_winner(&self) -> bool{
-        let mut i = 1;
-        let dim = self.dimension();
-        let mut diagonal_tokens: ~[char] = ~[];
+    pub fn lines(&self) -> ~[~[int]] {
+        let dim = self.dimension() as int;
+        let length = self.win_length as int;
+        let directions = [(0, 1), (1, 0), (1, 1), (1, -1)];
+        let mut lines = ~[];
 
-        do dim.times {
-            diagonal_tokens.push(self.spaces[(dim - 1) * i]);
-            i += 1;
-        }
+        for row in range(0, dim) {
+            for column in range(0, dim) {
+                for &(row_step, column_step) in directions.iter() {
+                    let last_row = row + row_step * (length - 1);
+                    let last_column = column + column_step * (length - 1);
 
-        self.is_all_same_token(diagonal_tokens)
-    }
+                    if last_row < dim && last_column >= 0 && last_column < dim {
+                        let line = range(0, length).map( |i: int|
+                            (row + row_step * i) * dim + column + column_step * i
+                        ).collect::<~[int]>();
 
-    fn is_all_same_token(&self, tokens: ~[char]) -> bool {
-        let first_token = tokens[0];
+                        lines.push(line);
+                    }
+                }
+            }
+        }
 
-        tokens.iter().all( |x: &char| *x == first_token) && first_token != ' '
+        lines
     }
 
-    pub fn winner(&self) -> Option<char> {
-        let mut rows = self.spaces.chunk_iter(3);
-        let transposed = self.transpose();
-        let mut columns = transposed.spaces.chunk_iter(3);
-
-        for row in rows {
-            if self.is_winning(row) {
-                return Some(row[0]);
-            }
-        }
+    pub fn winning_line(&self) -> Option<~[int]> {
+        for line in self.lines().iter() {
+            let cells = line.iter().map( |&i: &int| self.spaces[i] ).collect::<~[Cell]>();
 
-        for column in columns {
-            if self.is_winning(column) {
-                return Some(column[0]);
+            if self.is_all_same_token(cells) {
+                return Some(line.clone());
             }
         }
 
+        None
+    }
 
-        if self.has_slash_diagonal_winner() && self.spaces[self.dimension() - 1] != ' ' {
-            return Some(self.spaces[self.dimension() - 1]);
-        }
-
-        if self.has_backslash_diagonal_winner() && self.spaces[0] != ' ' {
-            return Some(self.spaces[0]);
+    pub fn winner(&self) -> Option<Token> {
+        match self.winning_line() {
+            Some(line) => self.spaces[line[0]].token(),
+            None       => None
         }
-
-        None
     }
 
-    fn somebody_won(&self) -> bool {
-        !(self.winner() == None)
+    pub fn outcome(&self) -> GameOutcome {
+        match self.winning_line() {
+            Some(line) => {
+                let token = self.spaces[line[0]].token().unwrap();
+                Win { token: token, line: line }
+            },
+
+            None => if self.board_is_full() { Draw } else { InProgress }
+        }
     }
 
     fn board_is_full(&self) -> bool {
@@ -190,7 +249,7 @@ impl Board {
     }
 
     pub fn is_game_over(&self) -> bool {
-        self.somebody_won() || self.board_is_full()
+        self.outcome() != InProgress
     }
 
     pub fn available_spaces(&self) -> ~[int] {
@@ -199,7 +258,7 @@ impl Board {
         let size = self.spaces.len();
 
         do size.times {
-            if self.spaces[i] == ' ' {
+            if self.spaces[i] == Empty {
                 available_spaces.push(i);
             }
 
@@ -208,11 +267,26 @@ impl Board {
 
         available_spaces
     }
+
+    // The spaces where `token` would complete a line, whoever's turn it is.
+    pub fn winning_moves(&self, token: Token) -> ~[int] {
+        self.available_spaces().move_iter().filter( |&index: &int|
+            self.with_to_move(token).place(index).winner() == Some(token)
+        ).collect()
+    }
+
+    // The spaces where `token` would leave itself two ways to win at once.
+    pub fn fork_moves(&self, token: Token) -> ~[int] {
+        self.available_spaces().move_iter().filter( |&index: &int|
+            self.with_to_move(token).place(index).winning_moves(token).len() >= 2
+        ).collect()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::vec::from_elem;
 
     #[test]
     fn has_9_spaces() {
@@ -221,12 +295,21 @@ mod test {
         assert_eq!(9, board.spaces.len());
     }
 
+    #[test]
+    fn can_create_a_board_of_any_size() {
+        let board = Board::with_size(4);
+
+        assert_eq!(16, board.spaces.len());
+        assert_eq!(4, board.dimension());
+        assert_eq!(25, Board::with_size(5).spaces.len());
+    }
+
     #[test]
     fn can_create_empty_board() {
         let board = Board::new();
 
         for space in board.spaces.iter() {
-            assert_eq!(' ', *space);
+            assert_eq!(Empty, *space);
         }
     }
 
@@ -236,8 +319,8 @@ mod test {
                                              ' ',' ',' ',
                                              ' ',' ',' ' ]);
 
-        assert_eq!('x', board.spaces[0]);
-        assert_eq!('o', board.spaces[1]);
+        assert_eq!(Filled(X), board.spaces[0]);
+        assert_eq!(Filled(O), board.spaces[1]);
     }
 
     #[test]
@@ -247,9 +330,9 @@ mod test {
         board = board.place(0);
         board = board.place(1);
 
-        assert_eq!('x', board.spaces[0]);
-        assert_eq!('o', board.spaces[1]);
-        assert_eq!(' ', board.spaces[2]);
+        assert_eq!(Filled(X), board.spaces[0]);
+        assert_eq!(Filled(O), board.spaces[1]);
+        assert_eq!(Empty, board.spaces[2]);
     }
 
     #[test]
@@ -259,17 +342,45 @@ mod test {
         board = board.place(0);
         board = board.place(0);
 
-        assert_eq!('x', board.spaces[0]);
-        assert_eq!('o', board.current_token());
+        assert_eq!(Filled(X), board.spaces[0]);
+        assert_eq!(O, board.current_token());
     }
 
     #[test]
     fn knows_the_current_token() {
         let mut board = Board::new();
-        assert_eq!('x', board.current_token());
+        assert_eq!(X, board.current_token());
 
         board = board.place(0);
-        assert_eq!('o', board.current_token());
+        assert_eq!(O, board.current_token());
+    }
+
+    #[test]
+    fn can_choose_who_moves_first() {
+        let mut board = Board::new().with_to_move(O);
+        assert_eq!(O, board.current_token());
+
+        board = board.place(4);
+        assert_eq!(Filled(O), board.spaces[4]);
+        assert_eq!(X, board.current_token());
+
+        board = board.place(4);
+        assert_eq!(X, board.current_token());
+    }
+
+    #[test]
+    fn works_out_who_moves_next_from_the_spaces() {
+        let x_to_move = Board::new_from_spaces(~['x','o',' ',
+                                                 ' ',' ',' ',
+                                                 ' ',' ',' ' ]);
+
+        let o_to_move = Board::new_from_spaces(~['x','o',' ',
+                                                 ' ','x',' ',
+                                                 ' ',' ',' ' ]);
+
+        assert_eq!(X, x_to_move.current_token());
+        assert_eq!(O, o_to_move.current_token());
+        assert_eq!(O, x_to_move.with_to_move(O).current_token());
     }
 
     #[test]
@@ -286,28 +397,68 @@ mod test {
                                                       ' ',' ',' ',
                                                       'x','x','x' ])];
 
-        for board in x_wins_boards.iter() { assert_eq!(Some('x'), board.winner()) }
+        for board in x_wins_boards.iter() { assert_eq!(Some(X), board.winner()) }
 
         let o_wins_board = Board::new_from_spaces(~['o','o','o',
                                                     'x','x',' ',
                                                     'x',' ',' ' ]);
 
-        assert_eq!(Some('o'), o_wins_board.winner())
+        assert_eq!(Some(O), o_wins_board.winner())
     }
 
     #[test]
     fn can_transpose_the_spaces() {
-        let board = Board::new_from_spaces(~['0','1','2',
-                                             '3','4','5',
-                                             '6','7','8' ]);
+        let board = Board::new_from_spaces(~['x','o',' ',
+                                             ' ','x',' ',
+                                             'o',' ',' ' ]);
+
+        let transposed_board = Board::new_from_spaces(~['x',' ','o',
+                                                        'o','x',' ',
+                                                        ' ',' ',' ' ]);
 
-        let transposed_board = Board::new_from_spaces(~['0','3','6',
-                                                        '1','4','7',
-                                                        '2','5','8' ]);
+        assert_eq!(transposed_board, board.transpose());
+    }
+
+    #[test]
+    fn can_transpose_a_larger_board() {
+        let board = Board::new_from_spaces(~['x','o',' ',' ',
+                                             ' ',' ','x',' ',
+                                             ' ',' ',' ','o',
+                                             'x',' ',' ',' ' ]);
+
+        let transposed_board = Board::new_from_spaces(~['x',' ',' ','x',
+                                                        'o',' ',' ',' ',
+                                                        ' ','x',' ',' ',
+                                                        ' ',' ','o',' ' ]);
 
         assert_eq!(transposed_board, board.transpose());
     }
 
+    #[test]
+    #[should_fail]
+    fn rejects_unknown_symbols() {
+        Board::new_from_spaces(~['x','q',' ',
+                                 ' ',' ',' ',
+                                 ' ',' ',' ' ]);
+    }
+
+    #[test]
+    fn can_create_from_cells() {
+        let board = Board::new_from_cells(~[Filled(X), Empty, Empty,
+                                            Empty, Filled(O), Empty,
+                                            Empty, Empty, Empty ]);
+
+        assert_eq!(Filled(X), board.spaces[0]);
+        assert_eq!(Filled(O), board.spaces[4]);
+        assert_eq!(X, board.current_token());
+    }
+
+    #[test]
+    fn knows_the_other_token() {
+        assert_eq!(O, X.other());
+        assert_eq!(X, O.other());
+    }
+
     #[test]
     fn knows_someone_wins_when_they_have_a_column() {
         let x_wins_boards = [Board::new_from_spaces(~['x','o',' ',
@@ -322,13 +473,13 @@ mod test {
                                                       ' ',' ','x',
                                                       ' ',' ','x' ])];
 
-        for board in x_wins_boards.iter() { assert_eq!(Some('x'), board.winner()) }
+        for board in x_wins_boards.iter() { assert_eq!(Some(X), board.winner()) }
 
         let o_wins_board = Board::new_from_spaces(~['o','x','x',
                                                     'o','x',' ',
                                                     'o',' ',' ' ]);
 
-        assert_eq!(Some('o'), o_wins_board.winner())
+        assert_eq!(Some(O), o_wins_board.winner())
     }
 
     #[test]
@@ -341,13 +492,99 @@ mod test {
                                                       'o','x',' ',
                                                       'x',' ',' ' ])];
 
-        for board in x_wins_boards.iter() { assert_eq!(Some('x'), board.winner()) }
+        for board in x_wins_boards.iter() { assert_eq!(Some(X), board.winner()) }
 
         let o_wins_board = Board::new_from_spaces(~['o','x','x',
                                                     ' ','o',' ',
                                                     ' ','x','o' ]);
 
-        assert_eq!(Some('o'), o_wins_board.winner())
+        assert_eq!(Some(O), o_wins_board.winner())
+    }
+
+    #[test]
+    fn knows_someone_wins_on_a_larger_board() {
+        let x_wins_boards = [Board::new_from_spaces(~[' ',' ',' ',' ',
+                                                      'x','x','x','x',
+                                                      'o','o',' ','o',
+                                                      ' ','o',' ',' ' ]),
+
+                             Board::new_from_spaces(~['o',' ','x',' ',
+                                                      ' ','o','x',' ',
+                                                      'o',' ','x',' ',
+                                                      ' ',' ','x','o' ]),
+
+                             Board::new_from_spaces(~['x','o',' ','o',
+                                                      ' ','x',' ',' ',
+                                                      'o',' ','x',' ',
+                                                      ' ',' ','o','x' ]),
+
+                             Board::new_from_spaces(~['o',' ','o','x',
+                                                      ' ',' ','x',' ',
+                                                      ' ','x',' ','o',
+                                                      'x',' ',' ',' ' ])];
+
+        for board in x_wins_boards.iter() { assert_eq!(Some(X), board.winner()) }
+
+        let three_in_a_row = Board::new_from_spaces(~['x','x','x',' ',
+                                                      'o','o','o',' ',
+                                                      ' ',' ',' ',' ',
+                                                      ' ',' ',' ',' ' ]);
+
+        assert_eq!(None, three_in_a_row.winner());
+    }
+
+    #[test]
+    fn finds_every_line_of_the_win_length() {
+        assert_eq!(8, Board::new().lines().len());
+        assert_eq!(24, Board::with_rules(4, 3).lines().len());
+        assert!(Board::with_rules(4, 3).lines().contains(&~[1, 6, 11]));
+        assert!(Board::with_rules(4, 3).lines().contains(&~[7, 10, 13]));
+    }
+
+    #[test]
+    fn knows_someone_wins_with_a_shorter_win_length() {
+        let x_wins_boards = [Board::new_from_spaces(~[' ','x','o',' ',
+                                                      'o',' ','x',' ',
+                                                      ' ',' ',' ','x',
+                                                      ' ',' ','o',' ' ]).with_win_length(3),
+
+                             Board::new_from_spaces(~[' ',' ',' ',' ',
+                                                      ' ','o',' ','x',
+                                                      ' ','o','x',' ',
+                                                      ' ','x',' ',' ' ]).with_win_length(3)];
+
+        for board in x_wins_boards.iter() { assert_eq!(Some(X), board.winner()) }
+
+        let two_in_a_row = Board::new_from_spaces(~['x','x',' ',' ',
+                                                    'o','o',' ',' ',
+                                                    ' ',' ',' ',' ',
+                                                    ' ',' ',' ',' ' ]).with_win_length(3);
+
+        assert_eq!(None, two_in_a_row.winner());
+    }
+
+    #[test]
+    fn knows_someone_wins_on_a_gomoku_board() {
+        let mut spaces = from_elem(225, ' ');
+        let five_in_a_diagonal = [3 * 15 + 10, 4 * 15 + 9, 5 * 15 + 8, 6 * 15 + 7, 7 * 15 + 6];
+
+        for &index in five_in_a_diagonal.iter() { spaces[index] = 'o' }
+
+        let board = Board::new_from_spaces(spaces.clone()).with_win_length(5);
+        assert_eq!(Some(O), board.winner());
+
+        spaces[7 * 15 + 6] = ' ';
+        let four_in_a_diagonal = Board::new_from_spaces(spaces).with_win_length(5);
+        assert_eq!(None, four_in_a_diagonal.winner());
+    }
+
+    #[test]
+    fn keeps_the_win_length_when_moving() {
+        let board = Board::with_rules(4, 3).place(0).place(4).place(1).place(5);
+
+        assert_eq!(3, board.win_length);
+        assert!(!board.is_game_over());
+        assert_eq!(Win { token: X, line: ~[0, 1, 2] }, board.try_move(2).unwrap().outcome());
     }
 
     #[test]
@@ -417,20 +654,47 @@ mod test {
     }
 
     #[test]
-    fn sets_a_flash_message_for_invalid_moves() {
+    fn returns_an_error_for_invalid_moves() {
         let board = Board::new_from_spaces(~['x','o',' ',
                                              ' ','x',' ',
                                              ' ','o',' ' ]);
 
-        let space_already_taken_board = board.try_move(0);
-        let invalid_space_board = board.try_move(1000);
+        assert_eq!(Err(Occupied), board.try_move(0));
+        assert_eq!(Err(OutOfBounds), board.try_move(1000));
+        assert_eq!(Err(OutOfBounds), board.try_move(-1));
+    }
+
+    #[test]
+    fn returns_an_error_for_moves_after_the_game_is_over() {
+        let board = Board::new_from_spaces(~['x','x','x',
+                                             'o','o',' ',
+                                             ' ',' ',' ' ]);
+
+        assert_eq!(Err(GameAlreadyOver), board.try_move(5));
+    }
+
+    #[test]
+    fn knows_the_bounds_of_a_larger_board() {
+        let board = Board::with_size(4);
+
+        assert_eq!(Err(OutOfBounds), board.try_move(16));
+        assert!(board.try_move(15).is_ok());
+    }
+
+    #[test]
+    fn alternates_tokens_on_an_even_sized_board() {
+        let mut board = Board::with_size(4);
+        assert_eq!(X, board.current_token());
 
-        assert_eq!(Some(~"That space is already taken."), space_already_taken_board.flash_message);
-        assert_eq!(Some(~"Please choose a number from 0 to 8."), invalid_space_board.flash_message);
+        board = board.place(5);
+        assert_eq!(O, board.current_token());
+
+        board = board.place(6);
+        assert_eq!(X, board.current_token());
     }
 
     #[test]
-    fn sets_a_game_over_flash_message() {
+    fn knows_the_outcome_of_the_game() {
         let board = Board::new_from_spaces(~['x','o',' ',
                                              ' ','x',' ',
                                              ' ','o',' ' ]);
@@ -439,11 +703,31 @@ mod test {
                                               'x','x','o',
                                               'x','o',' ' ]);
 
-        let x_wins_board = board.try_move(8);
-        let tie_game_board = board2.try_move(8);
+        let x_wins_board = board.try_move(8).unwrap();
+        let tie_game_board = board2.try_move(8).unwrap();
 
-        assert_eq!(Some(~"x wins!"), x_wins_board.flash_message);
-        assert_eq!(Some(~"Tie game!"), tie_game_board.flash_message);
+        assert_eq!(InProgress, board.outcome());
+        assert_eq!(Win { token: X, line: ~[0, 4, 8] }, x_wins_board.outcome());
+        assert_eq!(Draw, tie_game_board.outcome());
+    }
+
+    #[test]
+    fn knows_the_winning_line() {
+        let board = Board::new_from_spaces(~['o','x','x',
+                                             'o','x',' ',
+                                             'o',' ',' ' ]);
+
+        assert_eq!(Some(~[0, 3, 6]), board.winning_line());
+        assert_eq!(None, Board::new().winning_line());
+    }
+
+    #[test]
+    fn a_win_on_the_last_space_is_not_a_draw() {
+        let board = Board::new_from_spaces(~['x','o','x',
+                                             'o','x','o',
+                                             'o','x','x' ]);
+
+        assert_eq!(Win { token: X, line: ~[0, 4, 8] }, board.outcome());
     }
 
     #[test]
@@ -452,9 +736,29 @@ mod test {
                                                  ' ','x',' ',
                                                  ' ','o',' ' ]);
 
-        board = board.try_move(0);
+        board = board.try_move(0).unwrap();
 
-        assert_eq!('o', board.spaces[0]);
+        assert_eq!(Filled(O), board.spaces[0]);
     }
-}
 
+    #[test]
+    fn finds_the_winning_moves_for_either_token() {
+        let board = Board::new_from_spaces(~['x','x',' ',
+                                             'o','o',' ',
+                                             'x',' ',' ' ]);
+
+        assert_eq!(~[2], board.winning_moves(X));
+        assert_eq!(~[5], board.winning_moves(O));
+        assert_eq!(~[], Board::new().winning_moves(X));
+    }
+
+    #[test]
+    fn finds_the_forking_moves_for_either_token() {
+        let board = Board::new_from_spaces(~['x',' ',' ',
+                                             ' ','o',' ',
+                                             ' ',' ','x' ]);
+
+        assert_eq!(~[2, 6], board.fork_moves(X));
+        assert_eq!(~[], board.fork_moves(O));
+    }
+}
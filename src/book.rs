@@ -0,0 +1,428 @@
+use std::hashmap::HashMap;
+use std::io;
+use std::rand::{Rng, XorShiftRng};
+
+use ai::*;
+use board::*;
+use notation::*;
+use strategy::*;
+use symmetry::*;
+
+// Known good moves for the first few positions of a game, so a strategy can
+// play them instantly. Positions are stored in canonical form, and each
+// candidate move has a weight for how often it should be picked.
+pub struct OpeningBook {
+    positions: HashMap<~str, ~[(int, uint)]>
+}
+
+impl OpeningBook {
+    pub fn new() -> OpeningBook {
+        OpeningBook { positions: HashMap::new() }
+    }
+
+    pub fn len(&self) -> uint {
+        self.positions.len()
+    }
+
+    pub fn add(&mut self, board: &Board, index: int, weight: uint) {
+        let (canonical, transform) = board.canonical();
+        let canonical_index = transform.inverse().apply(index, board.dimension());
+        let candidates = self.positions.find_or_insert(canonical.to_notation(), ~[]);
+
+        match candidates.iter().position( |&(candidate, _)| candidate == canonical_index ) {
+            Some(position) => {
+                let (_, total) = candidates[position];
+                candidates[position] = (canonical_index, total + weight);
+            },
+
+            None => candidates.push((canonical_index, weight))
+        }
+    }
+
+    // Picks a candidate at random in proportion to its weight.
+    pub fn choose_move(&self, board: &Board, rng: &mut XorShiftRng) -> Option<int> {
+        let (canonical, transform) = board.canonical();
+
+        let candidates = match self.positions.find(&canonical.to_notation()) {
+            Some(candidates) => candidates,
+            None             => return None
+        };
+
+        let total = candidates.iter().fold(0u, |total: uint, &(_, weight): &(int, uint)| total + weight);
+
+        if total == 0 {
+            return None;
+        }
+
+        let mut draw = rng.gen_integer_range(0u, total);
+
+        for &(index, weight) in candidates.iter() {
+            if draw < weight {
+                return Some(transform.apply(index, board.dimension()));
+            }
+
+            draw -= weight;
+        }
+
+        None
+    }
+
+    // Credits the first `plies` moves of a finished game: two for the
+    // winner's moves, one for either side's in a draw, none for the loser's.
+    pub fn add_game(&mut self, start: &Board, moves: &[int], plies: uint) {
+        let end = moves.iter().fold(start.clone(), |board: Board, &index: &int| board.place(index));
+        let mut board = start.clone();
+
+        for &index in moves.iter().take(plies) {
+            let weight = match end.outcome() {
+                Win { token: winner, _ } if winner == board.current_token() => 2,
+                Draw                                                        => 1,
+                _                                                           => 0
+            };
+
+            if weight > 0 {
+                self.add(&board, index, weight);
+            }
+
+            board = board.place(index);
+        }
+    }
+
+    // Plays `strategy` against itself from `start`. It should play some
+    // moves at random, or every game will be the same.
+    pub fn from_self_play(start: &Board, strategy: @Strategy, games: uint, plies: uint) -> OpeningBook {
+        let mut book = OpeningBook::new();
+
+        do games.times {
+            let mut board = start.clone();
+            let mut moves = ~[];
+
+            while !board.is_game_over() {
+                let index = strategy.choose_move(&board).unwrap();
+
+                board = board.place(index);
+                moves.push(index);
+            }
+
+            book.add_game(start, moves, plies);
+        }
+
+        book
+    }
+
+    // Recorded games are one per line, as the indexes of their moves
+    // separated by spaces.
+    pub fn from_recorded_games(start: &Board, games: &str, plies: uint) -> Result<OpeningBook, ~str> {
+        let mut book = OpeningBook::new();
+
+        for (line_number, line) in games.line_iter().enumerate() {
+            let moves: ~[Option<int>] = line.word_iter().map( |index: &str| from_str::<int>(index) ).collect();
+            let error = ~"Line " + (line_number + 1).to_str() + " is not a game.";
+
+            if moves.iter().any( |index: &Option<int>| index.is_none() ) {
+                return Err(error);
+            }
+
+            let moves = moves.move_iter().map( |index: Option<int>| index.unwrap() ).collect::<~[int]>();
+            let mut board = start.clone();
+
+            for &index in moves.iter() {
+                match board.try_move(index) {
+                    Ok(new_board) => board = new_board,
+                    Err(*)        => return Err(error)
+                }
+            }
+
+            book.add_game(start, moves, plies);
+        }
+
+        Ok(book)
+    }
+
+    // One "notation<TAB>moves" line per position, each move written as
+    // "index:weight" and separated by commas.
+    pub fn to_tsv(&self) -> ~str {
+        let mut tsv = ~"";
+
+        for (notation, candidates) in self.positions.iter() {
+            let moves = candidates.map( |&(index, weight)| index.to_str() + ":" + weight.to_str() );
+
+            tsv.push_str(*notation + "\t" + moves.connect(",") + "\n");
+        }
+
+        tsv
+    }
+
+    pub fn from_tsv(tsv: &str) -> Result<OpeningBook, ~str> {
+        let mut book = OpeningBook::new();
+
+        for (line_number, line) in tsv.line_iter().enumerate() {
+            let fields: ~[&str] = line.split_iter('\t').collect();
+            let error = ~"Line " + (line_number + 1).to_str() + " is not a position and its moves.";
+
+            if fields.len() != 2 {
+                return Err(error);
+            }
+
+            let board = match Board::from_notation(fields[0]) {
+                Ok(board) => board,
+                Err(*)    => return Err(error)
+            };
+
+            let mut candidates = ~[];
+
+            for candidate in fields[1].split_iter(',') {
+                let parts: ~[&str] = candidate.split_iter(':').collect();
+
+                if parts.len() != 2 {
+                    return Err(error);
+                }
+
+                match (from_str::<int>(parts[0]), from_str::<uint>(parts[1])) {
+                    (Some(index), Some(weight)) if board.available_spaces().contains(&index) => {
+                        candidates.push((index, weight))
+                    },
+
+                    _ => return Err(error)
+                }
+            }
+
+            book.positions.insert(fields[0].to_owned(), candidates);
+        }
+
+        Ok(book)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), ~str> {
+        match io::file_writer(&Path(path), [io::Create, io::Truncate]) {
+            Ok(writer) => {
+                writer.write_str(self.to_tsv());
+                Ok(())
+            },
+
+            Err(error) => Err(error)
+        }
+    }
+
+    pub fn load(path: &str) -> Result<OpeningBook, ~str> {
+        match io::read_whole_file_str(&Path(path)) {
+            Ok(tsv)    => OpeningBook::from_tsv(tsv),
+            Err(error) => Err(error)
+        }
+    }
+}
+
+// Plays from the book while the position is in it, and leaves the rest of
+// the game to the strategy it wraps.
+pub struct Booked {
+    book: @OpeningBook,
+    strategy: @Strategy,
+    rng: @mut XorShiftRng
+}
+
+impl Booked {
+    pub fn new(book: @OpeningBook, strategy: @Strategy) -> Booked {
+        Booked::with_ai(AI::new(), book, strategy)
+    }
+
+    // Draws from the book with the engine's random numbers, so a seeded
+    // engine replays the same book moves.
+    pub fn with_ai(ai: AI, book: @OpeningBook, strategy: @Strategy) -> Booked {
+        Booked { book: book, strategy: strategy, rng: ai.rng }
+    }
+}
+
+impl Strategy for Booked {
+    fn name(&self) -> ~str {
+        self.strategy.name()
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        if board.is_game_over() {
+            return None;
+        }
+
+        // A book move that can't be played here, from a book edited by
+        // hand, is passed over rather than tried again every turn.
+        match self.book.choose_move(board, &mut *self.rng) {
+            Some(index) if board.try_move(index).is_ok() => Some(index),
+            _                                            => self.strategy.choose_move(board)
+        }
+    }
+
+    fn explain(&self, board: &Board) -> Option<~str> {
+        self.strategy.explain(board)
+    }
+
+    fn game_over(&self, outcome: &GameOutcome, token: Token) -> Result<(), ~str> {
+        self.strategy.game_over(outcome, token)
+    }
+}
+
+impl Registry {
+    // The same computer players, each checking the book before it searches.
+    pub fn with_book(&self, book: @OpeningBook) -> Registry {
+        let engine = self.engine;
+        let mut registry = Registry::with_engine(engine);
+
+        for &(ref name, factory) in self.entries.iter() {
+            registry.register(*name, || @Booked::with_ai(engine(), book, factory()) as @Strategy);
+        }
+
+        registry
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ai::*;
+    use board::*;
+    use notation::*;
+    use strategy::*;
+    use std::rand::XorShiftRng;
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::new_seeded(1, 2, 3, 4)
+    }
+
+    #[test]
+    fn shares_moves_between_symmetric_positions() {
+        let mut book = OpeningBook::new();
+        book.add(&Board::new().place(0), 4, 1);
+
+        assert_eq!(1, book.len());
+        assert_eq!(Some(4), book.choose_move(&Board::new().place(8), &mut rng()));
+    }
+
+    #[test]
+    fn maps_moves_back_onto_the_board_it_was_asked_about() {
+        let mut book = OpeningBook::new();
+        book.add(&Board::new().place(0), 1, 1);
+
+        let index = book.choose_move(&Board::new().place(8), &mut rng()).unwrap();
+
+        assert!(index == 5 || index == 7);
+    }
+
+    #[test]
+    fn picks_only_moves_with_weight() {
+        let mut book = OpeningBook::new();
+        book.add(&Board::new(), 4, 3);
+        book.add(&Board::new(), 0, 0);
+
+        do 20.times {
+            assert_eq!(Some(4), book.choose_move(&Board::new(), &mut rng()));
+        }
+
+        assert_eq!(None, book.choose_move(&Board::new().place(4), &mut rng()));
+    }
+
+    #[test]
+    fn credits_the_winner_of_a_recorded_game() {
+        let book = OpeningBook::from_recorded_games(&Board::new(), "4 0 1 7 2 6 8 3\n0 3 1 4 2", 2).unwrap();
+
+        assert_eq!(~[(0, 2)], *book.positions.find(&Board::new().to_notation()).unwrap());
+        assert_eq!(2, book.len());
+    }
+
+    #[test]
+    fn rejects_recorded_games_with_illegal_moves() {
+        assert_eq!(Err(~"Line 2 is not a game."), OpeningBook::from_recorded_games(&Board::new(), "4 0\n4 4", 2).map( |_| () ));
+        assert_eq!(Err(~"Line 1 is not a game."), OpeningBook::from_recorded_games(&Board::new(), "4 centre", 2).map( |_| () ));
+    }
+
+    #[test]
+    fn builds_a_book_from_self_play() {
+        let book = OpeningBook::from_self_play(&Board::new(), @RandomAvailable::new() as @Strategy, 20, 1);
+
+        assert_eq!(1, book.len());
+    }
+
+    #[test]
+    fn round_trips_through_tab_separated_values() {
+        let mut book = OpeningBook::new();
+        book.add(&Board::new(), 4, 3);
+        book.add(&Board::new().place(4), 0, 2);
+
+        let loaded = OpeningBook::from_tsv(book.to_tsv()).unwrap();
+
+        assert_eq!(book.positions, loaded.positions);
+        assert_eq!(Err(~"Line 1 is not a position and its moves."), OpeningBook::from_tsv(".../.../... x\t4").map( |_| () ));
+    }
+
+    #[test]
+    fn rejects_moves_off_the_board_or_onto_a_taken_space() {
+        let empty = Board::new().to_notation();
+        let taken = Board::new().place(4).to_notation();
+
+        assert_eq!(Err(~"Line 2 is not a position and its moves."),
+                   OpeningBook::from_tsv(empty + "\t4:1\n" + empty + "\t9:1\n").map( |_| () ));
+        assert_eq!(Err(~"Line 1 is not a position and its moves."), OpeningBook::from_tsv(empty + "\t-1:1\n").map( |_| () ));
+        assert_eq!(Err(~"Line 1 is not a position and its moves."), OpeningBook::from_tsv(taken + "\t4:1\n").map( |_| () ));
+    }
+
+    #[test]
+    fn searches_instead_of_playing_an_illegal_book_move() {
+        let mut book = OpeningBook::new();
+        book.positions.insert(Board::new().to_notation(), ~[(9, 1)]);
+
+        let booked = Booked::new(@book, @LowestAvailable as @Strategy);
+
+        assert_eq!(Some(0), booked.choose_move(&Board::new()));
+    }
+
+    #[test]
+    fn plays_from_the_book_before_searching() {
+        let mut book = OpeningBook::new();
+        book.add(&Board::new(), 8, 1);
+
+        let booked = Booked::new(@book, @LowestAvailable as @Strategy);
+
+        assert_eq!(Some(8), booked.choose_move(&Board::new()));
+        assert_eq!(Some(0), booked.choose_move(&Board::new().place(8)));
+    }
+
+    #[test]
+    fn explains_moves_as_the_wrapped_strategy_does() {
+        let booked = Booked::new(@OpeningBook::new(), @RuleBased as @Strategy);
+
+        assert_eq!(Some(~"Take the center."), booked.explain(&Board::new()));
+    }
+
+    #[test]
+    fn wraps_every_registered_strategy_with_the_book() {
+        let mut book = OpeningBook::new();
+        book.add(&Board::new(), 8, 1);
+
+        let registry = Registry::with_builtins().with_book(@book);
+
+        assert_eq!(Registry::with_builtins().names(), registry.names());
+        assert_eq!(Some(8), registry.find("Lowest available").unwrap().choose_move(&Board::new()));
+    }
+
+    fn book_moves_from_seed(book: @OpeningBook, seed: u32) -> ~[int] {
+        let booked = Registry::with_random_ties(seed).with_book(book).find("Lowest available").unwrap();
+        let mut moves = ~[];
+
+        do 10.times {
+            moves.push(booked.choose_move(&Board::new()).unwrap());
+        }
+
+        moves
+    }
+
+    #[test]
+    fn replays_the_same_book_moves_from_the_same_seed() {
+        let mut book = OpeningBook::new();
+        book.add(&Board::new(), 0, 1);
+        book.add(&Board::new(), 1, 1);
+        book.add(&Board::new(), 4, 1);
+
+        let book = @book;
+        let moves = book_moves_from_seed(book, 7);
+
+        assert_eq!(moves, book_moves_from_seed(book, 7));
+        assert!(moves.iter().any( |&index: &int| index != moves[0] ));
+    }
+}
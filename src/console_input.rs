@@ -2,6 +2,13 @@ use std::io::*;
 
 use console_reader::*;
 
+// What a human can type on their turn.
+#[deriving(Eq)]
+pub enum Command {
+    Move(int),
+    Hint
+}
+
 struct ConsoleInput {
     reader: ConsoleReader
 }
@@ -18,6 +25,15 @@ impl ConsoleInput {
         from_str::<int>(input.trim())
     }
 
+    pub fn get_command(&self) -> Option<Command> {
+        let input = self.reader.read_line();
+
+        match input.trim() {
+            "hint" => Some(Hint),
+            number => from_str::<int>(number).map( |&index| Move(index) )
+        }
+    }
+
     pub fn clone(&self) -> ConsoleInput {
         ConsoleInput::new(self.reader.clone())
     }
@@ -41,5 +57,12 @@ mod test {
         assert_eq!(Some(1), io.get_int());
         assert_eq!(None, io_with_invalid_input.get_int());
     }
+
+    #[test]
+    fn gets_a_move_or_a_hint_command() {
+        assert_eq!(Some(Move(4)), create_io_with_mocks(~" 4\n").get_command());
+        assert_eq!(Some(Hint), create_io_with_mocks(~" hint \n").get_command());
+        assert_eq!(None, create_io_with_mocks(~"help\n").get_command());
+    }
 }
 
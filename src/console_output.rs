@@ -1,16 +1,25 @@
+use std::iter::Repeat;
 use std::str;
 use std::vec::*;
 
+use analysis::*;
 use board::*;
 use console_writer::*;
+use symbols::*;
 
 struct ConsoleOutput {
-    writer: @ConsoleWriter
+    writer: @ConsoleWriter,
+    symbols: Symbols
 }
 
 impl ConsoleOutput {
     pub fn new(writer: @ConsoleWriter) -> ConsoleOutput {
-        ConsoleOutput { writer: writer }
+        ConsoleOutput::with_symbols(writer, Symbols::default())
+    }
+
+    pub fn with_symbols(writer: @ConsoleWriter, symbols: Symbols) -> ConsoleOutput {
+        ConsoleOutput { writer: writer,
+                        symbols: symbols }
     }
 
     pub fn print_board(&self, board: Board) -> @ConsoleWriter {
@@ -21,25 +30,98 @@ impl ConsoleOutput {
 
     pub fn printable_board(&self, board: Board) -> ~str {
         let mut i = -1;
+        let dim = board.dimension();
+        let winning_line = match board.outcome() {
+            Win { line: line, _ } => line,
+            _                     => ~[]
+        };
+
         let spaces = do flat_map(board.spaces) |&space| {
             i += 1;
-            ~[self.printable_space(i, space)]
+            let token = self.symbols.cell_to_char(space);
+
+            if winning_line.contains(&i) {
+                ~[self.printable_winning_space(i, token, dim)]
+            } else {
+                ~[self.printable_space(i, token, dim)]
+            }
         };
 
         self.flatten(spaces) + self.printable_error_message(board.flash_message)
     }
 
-    fn printable_space(&self, index: int, token: char) -> ~str {
-        let printable_token = " " + str::from_char(token) + " ";
+    fn printable_space(&self, index: int, token: char, dim: uint) -> ~str {
+        self.printable_cell(" " + str::from_char(token) + " ", index, dim)
+    }
+
+    fn printable_winning_space(&self, index: int, token: char, dim: uint) -> ~str {
+        self.printable_cell("[" + str::from_char(token) + "]", index, dim)
+    }
 
+    fn printable_cell(&self, printable_token: ~str, index: int, dim: uint) -> ~str {
         let grid_output =
-            if self.is_bottom_right_corner(index) { "" }
-            else if self.is_right_edge(index)     { "\n---+---+---\n" }
-            else                                  { "|" };
+            if self.is_bottom_right_corner(index, dim) { ~"" }
+            else if self.is_right_edge(index, dim)     { "\n" + self.row_separator(dim) + "\n" }
+            else                                       { ~"|" };
 
         printable_token + grid_output
     }
 
+    fn row_separator(&self, dim: uint) -> ~str {
+        let cells: ~[~str] = Repeat::new(~"---").take(dim).collect();
+
+        cells.connect("+")
+    }
+
+    pub fn printable_move_error(&self, error: MoveError, board: &Board) -> ~str {
+        match error {
+            OutOfBounds     => ~"Please choose a number from 0 to " + (board.spaces.len() - 1).to_str() + ".",
+            Occupied        => ~"That space is already taken.",
+            GameAlreadyOver => ~"The game is already over."
+        }
+    }
+
+    pub fn printable_unknown_command(&self, board: &Board) -> ~str {
+        ~"Please type a number from 0 to " + (board.spaces.len() - 1).to_str() + ", or hint."
+    }
+
+    pub fn printable_outcome(&self, outcome: &GameOutcome) -> Option<~str> {
+        match *outcome {
+            Win { token: token, _ } => Some(str::from_char(self.symbols.token_to_char(token)) + " wins!"),
+            Draw                    => Some(~"Tie game!"),
+            InProgress              => None
+        }
+    }
+
+    // Anything a player couldn't save is shown under the outcome.
+    pub fn printable_game_over(&self, outcome: &GameOutcome, errors: &[~str]) -> Option<~str> {
+        self.printable_outcome(outcome).map( |message: &~str| (~[message.clone()] + errors).connect("\n") )
+    }
+
+    pub fn printable_hint(&self, analysis: &MoveAnalysis, hints_left: uint) -> ~str {
+        let advice = match analysis.value {
+            WinIn(1)      => ~" to win.",
+            WinIn(moves)  => " to win in " + moves.to_str() + " moves.",
+            Drawn         => ~" to draw.",
+            Unknown       => ~", though it's too soon to tell how the game ends.",
+            LossIn(moves) => " to hold out for " + moves.to_str() + " moves."
+        };
+
+        ~"Hint: play " + analysis.index.to_str() + advice + " " + self.printable_hints_left(hints_left)
+    }
+
+    pub fn printable_no_hint(&self, hints_left: uint) -> ~str {
+        ~"No hint was found in time. " + self.printable_hints_left(hints_left)
+    }
+
+    pub fn printable_hints_left(&self, hints_left: uint) -> ~str {
+        match hints_left {
+            0 => ~"You have no hints left.",
+            1 => ~"You have 1 hint left.",
+            _ => ~"You have " + hints_left.to_str() + " hints left."
+        }
+    }
+
     fn printable_error_message(&self, error: Option<~str>) -> ~str {
         match error {
             Some(message) => ~"\n\n" + message,
@@ -47,12 +129,12 @@ impl ConsoleOutput {
         }
     }
 
-    fn is_right_edge(&self, index: int) -> bool {
-        index % 3 == 2
+    fn is_right_edge(&self, index: int, dim: uint) -> bool {
+        index % (dim as int) == (dim as int) - 1
     }
 
-    fn is_bottom_right_corner(&self, index: int) -> bool {
-        index == 8
+    fn is_bottom_right_corner(&self, index: int, dim: uint) -> bool {
+        index == (dim * dim) as int - 1
     }
 
     fn flatten(&self, arr: &[~str]) -> ~str {
@@ -81,13 +163,37 @@ impl ConsoleOutput {
 
         self.writer.println(menu_str)
     }
+
+    pub fn print_first_player_menu(&self) -> @ConsoleWriter {
+        let menu_str = ~"Who moves first in the first game?\n\n" +
+                        " 1) " + str::from_char(self.symbols.token_to_char(X)) + "\n" +
+                        " 2) " + str::from_char(self.symbols.token_to_char(O)) + "\n\n";
+
+        self.writer.println(menu_str)
+    }
+
+    pub fn print_computer_menu(&self, token: Token, names: &[~str]) -> @ConsoleWriter {
+        let mut menu_str = ~"Please select the computer playing " +
+                           str::from_char(self.symbols.token_to_char(token)) + ":\n\n";
+
+        for (i, name) in names.iter().enumerate() {
+            menu_str.push_str(" " + (i + 1).to_str() + ") " + *name + "\n");
+        }
+
+        menu_str.push_str("\n");
+
+        self.writer.println(menu_str)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use analysis::*;
     use board::*;
     use console_writer::*;
+    use strategy::*;
+    use symbols::*;
 
     fn output_with_fake_writer() -> ConsoleOutput {
         let mock_writer = MockWriter { printed_str: ~"" };
@@ -99,11 +205,82 @@ mod test {
     fn generates_a_string_for_each_space() {
         let output = output_with_fake_writer();
 
-        assert_eq!(~" x |",               output.printable_space(0, 'x'));
-        assert_eq!(~" o |",               output.printable_space(1, 'o'));
-        assert_eq!(~" x \n---+---+---\n", output.printable_space(2, 'x'));
-        assert_eq!(~" o \n---+---+---\n", output.printable_space(5, 'o'));
-        assert_eq!(~" o ",                output.printable_space(8, 'o'));
+        assert_eq!(~" x |",               output.printable_space(0, 'x', 3));
+        assert_eq!(~" o |",               output.printable_space(1, 'o', 3));
+        assert_eq!(~" x \n---+---+---\n", output.printable_space(2, 'x', 3));
+        assert_eq!(~" o \n---+---+---\n", output.printable_space(5, 'o', 3));
+        assert_eq!(~" o ",                output.printable_space(8, 'o', 3));
+    }
+
+    #[test]
+    fn generates_a_string_for_each_space_on_a_larger_board() {
+        let output = output_with_fake_writer();
+
+        assert_eq!(~" x |",                   output.printable_space(2, 'x', 4));
+        assert_eq!(~" o \n---+---+---+---\n", output.printable_space(3, 'o', 4));
+        assert_eq!(~" x |",                   output.printable_space(8, 'x', 4));
+        assert_eq!(~" o ",                    output.printable_space(15, 'o', 4));
+    }
+
+    #[test]
+    fn highlights_the_winning_spaces() {
+        let output = output_with_fake_writer();
+
+        assert_eq!(~"[x]|",               output.printable_winning_space(0, 'x', 3));
+        assert_eq!(~"[o]\n---+---+---\n", output.printable_winning_space(2, 'o', 3));
+
+        let board = Board::new_from_spaces(~['x','o',' ',
+                                             'o','x',' ',
+                                             ' ',' ','x' ]);
+
+        assert!(output.printable_board(board).contains("[x]| o |   \n"));
+    }
+
+    #[test]
+    fn describes_the_outcome_of_the_game() {
+        let output = output_with_fake_writer();
+
+        assert_eq!(Some(~"x wins!"), output.printable_outcome(&Win { token: X, line: ~[0, 1, 2] }));
+        assert_eq!(Some(~"Tie game!"), output.printable_outcome(&Draw));
+        assert_eq!(None, output.printable_outcome(&InProgress));
+    }
+
+    #[test]
+    fn shows_save_errors_under_the_outcome() {
+        let output = output_with_fake_writer();
+
+        assert_eq!(Some(~"Tie game!\ndisk full"), output.printable_game_over(&Draw, [~"disk full"]));
+        assert_eq!(Some(~"Tie game!"), output.printable_game_over(&Draw, []));
+        assert_eq!(None, output.printable_game_over(&InProgress, [~"disk full"]));
+    }
+
+    #[test]
+    fn describes_unknown_input() {
+        let output = output_with_fake_writer();
+
+        assert_eq!(~"Please type a number from 0 to 15, or hint.", output.printable_unknown_command(&Board::with_size(4)));
+    }
+
+    #[test]
+    fn says_when_no_hint_was_found() {
+        let output = output_with_fake_writer();
+
+        assert_eq!(~"No hint was found in time. You have 3 hints left.", output.printable_no_hint(3));
+    }
+
+    #[test]
+    fn describes_a_hint_and_the_hints_left() {
+        let output = output_with_fake_writer();
+        let win = MoveAnalysis { index: 8, value: WinIn(1), principal_variation: ~[8] };
+        let draw = MoveAnalysis { index: 4, value: Drawn, principal_variation: ~[4] };
+        let loss = MoveAnalysis { index: 0, value: LossIn(2), principal_variation: ~[0, 7] };
+        let unknown = MoveAnalysis { index: 5, value: Unknown, principal_variation: ~[5] };
+
+        assert_eq!(~"Hint: play 8 to win. You have 2 hints left.", output.printable_hint(&win, 2));
+        assert_eq!(~"Hint: play 4 to draw. You have 1 hint left.", output.printable_hint(&draw, 1));
+        assert_eq!(~"Hint: play 0 to hold out for 2 moves. You have no hints left.", output.printable_hint(&loss, 0));
+        assert_eq!(~"Hint: play 5, though it's too soon to tell how the game ends. You have 1 hint left.",
+                   output.printable_hint(&unknown, 1));
     }
 
     #[test]
@@ -121,11 +298,23 @@ mod test {
                                                  ' ',' ',' ',
                                                  ' ',' ','x' ]);
 
-        board = board.try_move(0);
+        let error = board.try_move(0).unwrap_err();
+        board = board.with_flash_message(Some(output.printable_move_error(error, &board)));
 
         assert!(output.printable_board(board).contains("That space is already taken."));
     }
 
+    #[test]
+    fn describes_each_move_error() {
+        let output = output_with_fake_writer();
+        let board = Board::new();
+
+        assert_eq!(~"Please choose a number from 0 to 8.", output.printable_move_error(OutOfBounds, &board));
+        assert_eq!(~"Please choose a number from 0 to 15.", output.printable_move_error(OutOfBounds, &Board::with_size(4)));
+        assert_eq!(~"That space is already taken.", output.printable_move_error(Occupied, &board));
+        assert_eq!(~"The game is already over.", output.printable_move_error(GameAlreadyOver, &board));
+    }
+
     #[test]
     fn can_print_a_board() {
         let mut output = output_with_fake_writer();
@@ -144,6 +333,43 @@ mod test {
                                      "   |   | x "));
     }
 
+    #[test]
+    fn can_print_a_larger_board() {
+        let mut output = output_with_fake_writer();
+
+        let board = Board::new_from_spaces(~['x',' ',' ',' ',
+                                             ' ','o',' ',' ',
+                                             ' ',' ',' ',' ',
+                                             ' ',' ',' ','x' ]);
+
+        output.writer = output.print_board(board);
+        let printed_str = output.writer.get_printed_str();
+
+        assert!(printed_str.contains(" x |   |   |   \n" +
+                                     "---+---+---+---\n" +
+                                     "   | o |   |   \n" +
+                                     "---+---+---+---\n" +
+                                     "   |   |   |   \n" +
+                                     "---+---+---+---\n" +
+                                     "   |   |   | x "));
+    }
+
+    #[test]
+    fn prints_the_board_with_its_symbols() {
+        let mock_writer = MockWriter { printed_str: ~"" };
+        let mut output = ConsoleOutput::with_symbols(@mock_writer, Symbols::new('X', 'O', '.'));
+
+        let board = Board::new_from_spaces(~['x','o',' ',
+                                             ' ',' ',' ',
+                                             ' ',' ','x' ]);
+
+        output.writer = output.print_board(board);
+        let printed_str = output.writer.get_printed_str();
+
+        assert!(printed_str.contains(" X | O | . \n"));
+        assert!(printed_str.ends_with(" . | . | X "));
+    }
+
     #[test]
     fn can_print_the_menu() {
         let mut output = output_with_fake_writer();
@@ -158,5 +384,32 @@ mod test {
                                      " 4) Computer vs. Computer\n" +
                                      " 5) Quit\n\n"));
     }
-}
 
+    #[test]
+    fn can_print_the_computer_menu() {
+        let mut output = output_with_fake_writer();
+
+        output.writer = output.print_computer_menu(O, Registry::with_builtins().names());
+        let printed_str = output.writer.get_printed_str();
+
+        assert!(printed_str.contains("Please select the computer playing o:\n\n" +
+                                     " 1) Random\n" +
+                                     " 2) Easy\n" +
+                                     " 3) Medium\n" +
+                                     " 4) Perfect\n" +
+                                     " 5) Lowest available\n"));
+        assert!(printed_str.ends_with(" 10) Tablebase\n\n"));
+    }
+
+    #[test]
+    fn can_print_the_first_player_menu() {
+        let mut output = output_with_fake_writer();
+
+        output.writer = output.print_first_player_menu();
+        let printed_str = output.writer.get_printed_str();
+
+        assert!(printed_str.contains("Who moves first in the first game?\n\n" +
+                                     " 1) x\n" +
+                                     " 2) o\n\n"));
+    }
+}
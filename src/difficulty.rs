@@ -0,0 +1,117 @@
+use ai::*;
+use board::*;
+use strategy::*;
+
+#[deriving(Clone, Eq)]
+pub enum Difficulty {
+    Random,
+    Easy,
+    Medium,
+    Perfect
+}
+
+impl Difficulty {
+    pub fn all() -> ~[Difficulty] {
+        ~[Random, Easy, Medium, Perfect]
+    }
+
+    // Menu choices count from 1, in the order of `all`.
+    pub fn from_choice(choice: int) -> Option<Difficulty> {
+        let difficulties = Difficulty::all();
+
+        if choice >= 1 && choice <= difficulties.len() as int {
+            Some(difficulties[choice - 1])
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> ~str {
+        match *self {
+            Random  => ~"Random",
+            Easy    => ~"Easy",
+            Medium  => ~"Medium",
+            Perfect => ~"Perfect"
+        }
+    }
+
+    pub fn strategy(&self) -> @Strategy {
+        self.strategy_with(AI::new())
+    }
+
+    // Named after the difficulty, so the player sees the same name in the
+    // menu and in the game.
+    pub fn strategy_with(&self, ai: AI) -> @Strategy {
+        @Levelled { difficulty: *self, strategy: self.engine_strategy_with(ai) } as @Strategy
+    }
+
+    fn engine_strategy_with(&self, ai: AI) -> @Strategy {
+        match *self {
+            Random  => @RandomAvailable::with_ai(ai) as @Strategy,
+            Easy    => @Careless::with_ai(ai, 0.3) as @Strategy,
+            Medium  => @Fallible::with_ai(ai, 0.2) as @Strategy,
+            Perfect => @Minimax::with_ai(ai) as @Strategy
+        }
+    }
+}
+
+struct Levelled {
+    difficulty: Difficulty,
+    strategy: @Strategy
+}
+
+impl Strategy for Levelled {
+    fn name(&self) -> ~str {
+        self.difficulty.name()
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        self.strategy.choose_move(board)
+    }
+
+    fn explain(&self, board: &Board) -> Option<~str> {
+        self.strategy.explain(board)
+    }
+
+    fn game_over(&self, outcome: &GameOutcome, token: Token) -> Result<(), ~str> {
+        self.strategy.game_over(outcome, token)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ai::*;
+
+    #[test]
+    fn reads_a_difficulty_from_a_menu_choice() {
+        assert_eq!(Some(Random), Difficulty::from_choice(1));
+        assert_eq!(Some(Perfect), Difficulty::from_choice(4));
+        assert_eq!(None, Difficulty::from_choice(0));
+        assert_eq!(None, Difficulty::from_choice(5));
+    }
+
+    #[test]
+    fn names_each_difficulty() {
+        let names = Difficulty::all().map( |difficulty: &Difficulty| difficulty.name() );
+
+        assert_eq!(~[~"Random", ~"Easy", ~"Medium", ~"Perfect"], names);
+    }
+
+    #[test]
+    fn calls_each_strategy_by_its_difficulty() {
+        for difficulty in Difficulty::all().iter() {
+            assert_eq!(difficulty.name(), difficulty.strategy().name());
+        }
+    }
+
+    #[test]
+    fn plays_perfectly_only_on_the_hardest_difficulty() {
+        let is_minimax = |difficulty: Difficulty| difficulty.engine_strategy_with(AI::new()).name() == ~"Minimax";
+
+        assert!(is_minimax(Perfect));
+        assert!(!is_minimax(Medium));
+        assert!(!is_minimax(Easy));
+        assert!(!is_minimax(Random));
+    }
+}
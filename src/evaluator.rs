@@ -0,0 +1,142 @@
+use bitboard::*;
+use board::*;
+
+// Guesses how good an unfinished position is for `token`. Higher is better,
+// and a position should score the same, negated, for the other token.
+pub trait Evaluator {
+    fn evaluate(&self, board: &Bitboard, token: Token) -> int;
+}
+
+// Rewards lines the opponent has not blocked, more so when they are one move
+// from winning, and tokens near the middle of the board.
+pub struct LineEvaluator {
+    open_line: int,
+    threat: int,
+    center: int
+}
+
+impl LineEvaluator {
+    pub fn new() -> LineEvaluator {
+        LineEvaluator { open_line: 1, threat: 10, center: 3 }
+    }
+
+    fn score_line(&self, own: uint, theirs: uint, win_length: uint) -> int {
+        if own > 0 && theirs == 0 {
+            self.score_open_line(own, win_length)
+        } else if theirs > 0 && own == 0 {
+            -self.score_open_line(theirs, win_length)
+        } else {
+            0
+        }
+    }
+
+    fn score_open_line(&self, tokens: uint, win_length: uint) -> int {
+        if tokens + 1 == win_length {
+            self.threat
+        } else {
+            self.open_line
+        }
+    }
+}
+
+impl Evaluator for LineEvaluator {
+    fn evaluate(&self, board: &Bitboard, token: Token) -> int {
+        let mut score = 0;
+
+        for &(x_count, o_count) in board.line_counts().iter() {
+            score += match token {
+                X => self.score_line(x_count, o_count, board.win_length()),
+                O => self.score_line(o_count, x_count, board.win_length())
+            };
+        }
+
+        for index in range(0, board.len() as int) {
+            match board.space(index) {
+                Filled(owner) if owner == token => score += self.center * centrality(index, board.dimension()),
+                Filled(*)                       => score -= self.center * centrality(index, board.dimension()),
+                Empty                           => ()
+            }
+        }
+
+        score
+    }
+}
+
+// Counts the rings between a space and the edge of the board, so the edge
+// is 0 and the middle of a 3x3 board is 1.
+fn centrality(index: int, dimension: uint) -> int {
+    let dim = dimension as int;
+    let row_offset = (2 * (index / dim) - (dim - 1)).abs();
+    let column_offset = (2 * (index % dim) - (dim - 1)).abs();
+
+    (dim - 1) / 2 - row_offset.max(&column_offset) / 2
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bitboard::*;
+    use board::*;
+
+    fn evaluate(spaces: ~[char], token: Token) -> int {
+        LineEvaluator::new().evaluate(&Bitboard::from_board(&Board::new_from_spaces(spaces)), token)
+    }
+
+    #[test]
+    fn scores_an_empty_board_as_even() {
+        assert_eq!(0, LineEvaluator::new().evaluate(&Bitboard::from_board(&Board::new()), X));
+    }
+
+    #[test]
+    fn prefers_the_center_to_a_corner() {
+        let center = evaluate(~[' ',' ',' ',
+                                ' ','x',' ',
+                                ' ',' ',' ' ], X);
+
+        let corner = evaluate(~['x',' ',' ',
+                                ' ',' ',' ',
+                                ' ',' ',' ' ], X);
+
+        assert!(center > corner);
+    }
+
+    #[test]
+    fn values_two_in_a_row_above_open_lines() {
+        let two_in_a_row = evaluate(~['x','x',' ',
+                                      ' ',' ',' ',
+                                      ' ',' ',' ' ], X);
+
+        let spread_out = evaluate(~['x',' ',' ',
+                                    ' ',' ','x',
+                                    ' ',' ',' ' ], X);
+
+        assert!(two_in_a_row > spread_out);
+    }
+
+    #[test]
+    fn scores_a_balanced_position_as_even() {
+        let balanced = evaluate(~['x',' ','o',
+                                  ' ',' ',' ',
+                                  ' ',' ',' ' ], X);
+
+        assert_eq!(0, balanced);
+    }
+
+    #[test]
+    fn scores_the_same_position_oppositely_for_each_token() {
+        let spaces = ~['x',' ',' ',
+                       ' ','o',' ',
+                       ' ','x',' ' ];
+
+        assert_eq!(evaluate(spaces.clone(), X), -evaluate(spaces, O));
+    }
+
+    #[test]
+    fn measures_how_far_a_space_is_from_the_edge() {
+        assert_eq!(1, centrality(4, 3));
+        assert_eq!(0, centrality(0, 3));
+        assert_eq!(2, centrality(12, 5));
+        assert_eq!(1, centrality(6, 5));
+        assert_eq!(1, centrality(5, 4));
+    }
+}
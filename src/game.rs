@@ -1,12 +1,23 @@
+use extra::time::precise_time_ns;
+use ai::*;
+use analysis::*;
 use board::*;
+use console_input::*;
 use console_output::*;
 use player::*;
 
+// Milliseconds a hint may spend analyzing by default, so larger boards
+// still answer.
+static HINT_BUDGET: u64 = 1000;
+
 struct Game {
     output: ConsoleOutput,
     board: Board,
     player1: Player,
-    player2: Player
+    player2: Player,
+    thinking_delay: u64,
+    hint_budget: u64,
+    hint_ai: AI
 }
 
 impl Game {
@@ -15,38 +26,132 @@ impl Game {
         Game { output: output,
                board: board,
                player1: player1,
-               player2: player2
+               player2: player2,
+               thinking_delay: 0,
+               hint_budget: HINT_BUDGET,
+               hint_ai: AI::new()
         }
     }
 
+    // Milliseconds to pause before a computer player moves, so its moves
+    // don't appear the instant the board is drawn.
+    pub fn with_thinking_delay(self, milliseconds: u64) -> Game {
+        let mut game = self;
+        game.thinking_delay = milliseconds;
+
+        game
+    }
+
+    // Milliseconds a hint may spend analyzing before giving up.
+    pub fn with_hint_budget(self, milliseconds: u64) -> Game {
+        let mut game = self;
+        game.hint_budget = milliseconds;
+
+        game
+    }
+
     pub fn next_turn(&self) -> Board {
         self.output.clear_screen();
-        let spaces = self.board.spaces.clone();
-
         self.output.print_board(self.board.clone());
 
-        let mut new_board = Board::new_from_spaces(spaces.clone());
+        let mut new_board = self.board.with_flash_message(None);
 
-        let move = if self.board.current_token() == 'x' {
-            self.player1.get_move(new_board.clone())
+        let player = if self.board.current_token() == X {
+            &self.player1
         } else {
-            self.player2.get_move(new_board.clone())
+            &self.player2
         };
 
-        match move {
-            Some(index) => new_board = self.board.try_move(index),
-            None        => ()
+        if player.is_computer() && self.thinking_delay > 0 {
+            ::std::rt::io::timer::sleep(self.thinking_delay);
+        }
+
+        let command = player.get_command(new_board.clone());
+
+        match command {
+            Some(Move(index)) => new_board = self.play_move(index, player),
+            Some(Hint)        => new_board = self.show_hint(player),
+            None              => if !player.is_computer() {
+                new_board = self.board.with_flash_message(Some(self.output.printable_unknown_command(&self.board)));
+            }
         }
 
         new_board
     }
+
+    // Takes turns until the game is over, and returns the final board.
+    pub fn play_out(&mut self) -> Board {
+        while !self.board.is_game_over() {
+            self.board = self.next_turn();
+        }
+
+        self.board.clone()
+    }
+
+    // Flashes the best move without playing it, so it is still the same
+    // player's turn. A hint is only used up when there is one to show.
+    fn show_hint(&self, player: &Player) -> Board {
+        if player.hints_left() == 0 {
+            return self.board.with_flash_message(Some(self.output.printable_hints_left(0)));
+        }
+
+        let mut search = Search::with_deadline(EndOfGame, precise_time_ns() + self.hint_budget * 1000000);
+
+        let message = match self.hint_ai.best_analysis_with(&self.board, &mut search) {
+            Some(analysis) => {
+                player.take_hint();
+                self.output.printable_hint(&analysis, player.hints_left())
+            },
+
+            None => self.output.printable_no_hint(player.hints_left())
+        };
+
+        self.board.with_flash_message(Some(message))
+    }
+
+    // A computer that can say why it moved has its reason flashed, unless
+    // the game just ended.
+    fn play_move(&self, index: int, player: &Player) -> Board {
+        match self.board.try_move(index) {
+            Ok(board)  => {
+                let outcome = board.outcome();
+                let mut errors = ~[];
+
+                if outcome != InProgress {
+                    for &(player, token) in [(&self.player1, X), (&self.player2, O)].iter() {
+                        match player.game_over(&outcome, token) {
+                            Ok(())     => (),
+                            Err(error) => errors.push(error)
+                        }
+                    }
+                }
+
+                let message = match self.output.printable_game_over(&outcome, errors) {
+                    Some(message) => Some(message),
+                    None          => player.explain_move(&self.board)
+                };
+
+                board.with_flash_message(message)
+            },
+
+            Err(error) => {
+                let message = self.output.printable_move_error(error, &self.board);
+                self.board.with_flash_message(Some(message))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use ai::*;
     use board::*;
     use player::*;
+    use menace::*;
+    use strategy::*;
+    use extra::time::precise_time_ns;
+    use std::rand::XorShiftRng;
     use console_reader::*;
     use console_writer::*;
     use console_input::*;
@@ -56,12 +161,12 @@ mod test {
         let fake_reader = MockReader { str_in_stdin: input };
         let fake_input = ConsoleInput { reader: fake_reader };
 
-        HumanPlayer { input: fake_input.clone() }
+        Player::new_human(fake_input.clone())
     }
 
     fn create_fake_output() -> ConsoleOutput {
         let fake_writer = @MockWriter { printed_str: ~"" };
-        ConsoleOutput { writer: fake_writer }
+        ConsoleOutput::new(fake_writer)
     }
 
     #[test]
@@ -74,11 +179,207 @@ mod test {
         let mut game = Game::new(fake_output, board, fake_player1, fake_player2);
 
         game.board = game.next_turn();
-        assert_eq!('x', game.board.spaces[0]);
-        assert_eq!(' ', game.board.spaces[1]);
+        assert_eq!(Filled(X), game.board.spaces[0]);
+        assert_eq!(Empty, game.board.spaces[1]);
 
         game.board = game.next_turn();
-        assert_eq!('o', game.board.spaces[1]);
+        assert_eq!(Filled(O), game.board.spaces[1]);
     }
-}
 
+    #[test]
+    fn lets_o_move_first() {
+        let board = Board::new().with_to_move(O);
+        let fake_player1 = create_human_with_input(~"0");
+        let fake_player2 = create_human_with_input(~"4");
+        let fake_output = create_fake_output();
+
+        let mut game = Game::new(fake_output, board, fake_player1, fake_player2);
+
+        game.board = game.next_turn();
+        assert_eq!(Filled(O), game.board.spaces[4]);
+        assert_eq!(Empty, game.board.spaces[0]);
+
+        game.board = game.next_turn();
+        assert_eq!(Filled(X), game.board.spaces[0]);
+    }
+
+    #[test]
+    fn flashes_the_outcome_when_the_game_ends() {
+        let board = Board::new_from_spaces(~['x','x',' ',
+                                             'o','o',' ',
+                                             ' ',' ',' ' ]);
+        let fake_player1 = create_human_with_input(~"2");
+        let fake_player2 = create_human_with_input(~"5");
+        let fake_output = create_fake_output();
+
+        let mut game = Game::new(fake_output, board, fake_player1, fake_player2);
+
+        game.board = game.next_turn();
+        assert_eq!(Some(~"x wins!"), game.board.flash_message);
+        assert!(game.board.is_game_over());
+    }
+
+    #[test]
+    fn flashes_a_message_for_an_invalid_move() {
+        let board = Board::new();
+        let fake_player1 = create_human_with_input(~"9");
+        let fake_player2 = create_human_with_input(~"1");
+        let fake_output = create_fake_output();
+
+        let mut game = Game::new(fake_output, board, fake_player1, fake_player2);
+
+        game.board = game.next_turn();
+        assert_eq!(Some(~"Please choose a number from 0 to 8."), game.board.flash_message);
+        assert_eq!(X, game.board.current_token());
+    }
+
+    #[test]
+    fn flashes_a_message_for_unknown_input() {
+        let fake_player1 = create_human_with_input(~"claws");
+        let fake_player2 = create_human_with_input(~"1");
+
+        let game = Game::new(create_fake_output(), Board::new(), fake_player1, fake_player2);
+        let new_board = game.next_turn();
+
+        assert_eq!(Some(~"Please type a number from 0 to 8, or hint."), new_board.flash_message);
+        assert_eq!(X, new_board.current_token());
+    }
+
+    #[test]
+    fn flashes_a_hint_without_playing_it() {
+        let board = Board::new_from_spaces(~[' ','o','x',
+                                             ' ','o','x',
+                                             ' ',' ',' ' ]);
+        let fake_player1 = create_human_with_input(~"hint");
+        let fake_player2 = create_human_with_input(~"0");
+
+        let game = Game::new(create_fake_output(), board.clone(), fake_player1, fake_player2);
+        let new_board = game.next_turn();
+
+        assert_eq!(Some(~"Hint: play 8 to win. You have 2 hints left."), new_board.flash_message);
+        assert_eq!(board.spaces, new_board.spaces);
+        assert_eq!(X, new_board.current_token());
+    }
+
+    #[test]
+    fn keeps_the_hint_when_there_is_none_to_show() {
+        let fake_reader = MockReader { str_in_stdin: ~"hint" };
+        let fake_player1 = Player::new_human_with_hints(ConsoleInput { reader: fake_reader }, 2);
+        let fake_player2 = create_human_with_input(~"0");
+
+        let game = Game::new(create_fake_output(), Board::with_size(4), fake_player1.clone(), fake_player2)
+                       .with_hint_budget(0);
+
+        assert_eq!(Some(~"No hint was found in time. You have 2 hints left."), game.next_turn().flash_message);
+        assert_eq!(2, fake_player1.hints_left());
+    }
+
+    #[test]
+    fn stops_giving_hints_when_they_run_out() {
+        let fake_reader = MockReader { str_in_stdin: ~"hint" };
+        let fake_player1 = Player::new_human_with_hints(ConsoleInput { reader: fake_reader }, 0);
+        let fake_player2 = create_human_with_input(~"0");
+
+        let game = Game::new(create_fake_output(), Board::new(), fake_player1, fake_player2);
+
+        assert_eq!(Some(~"You have no hints left."), game.next_turn().flash_message);
+    }
+
+    #[test]
+    fn pauses_before_a_computer_moves() {
+        let board = Board::new();
+        let computer = Player::new_computer(@LowestAvailable as @Strategy);
+        let fake_player2 = create_human_with_input(~"1");
+        let fake_output = create_fake_output();
+
+        let game = Game::new(fake_output, board, computer, fake_player2).with_thinking_delay(50);
+        let start = precise_time_ns();
+        let new_board = game.next_turn();
+
+        assert!(precise_time_ns() - start >= 50 * 1000000);
+        assert_eq!(Filled(X), new_board.spaces[0]);
+    }
+
+    #[test]
+    fn flashes_the_rule_a_computer_followed() {
+        let computer = Player::new_computer(@RuleBased as @Strategy);
+        let fake_player2 = create_human_with_input(~"0");
+
+        let game = Game::new(create_fake_output(), Board::new(), computer, fake_player2);
+        let new_board = game.next_turn();
+
+        assert_eq!(Filled(X), new_board.spaces[4]);
+        assert_eq!(Some(~"Take the center."), new_board.flash_message);
+    }
+
+    #[test]
+    fn plays_a_whole_game_on_a_larger_board() {
+        let player1 = Player::new_computer(@LowestAvailable as @Strategy);
+        let player2 = Player::new_computer(@LowestAvailable as @Strategy);
+
+        let mut game = Game::new(create_fake_output(), Board::with_size(4), player1, player2);
+        let board = game.play_out();
+
+        assert_eq!(16, board.spaces.len());
+        assert_eq!(Some(X), board.winner());
+        assert_eq!(Some(~"x wins!"), board.flash_message);
+    }
+
+    #[test]
+    fn wins_with_a_shorter_line_than_the_board() {
+        let player1 = Player::new_computer(@LowestAvailable as @Strategy);
+        let player2 = Player::new_computer(@LowestAvailable as @Strategy);
+
+        let mut game = Game::new(create_fake_output(), Board::with_rules(5, 4), player1, player2);
+        let board = game.play_out();
+
+        assert_eq!(Some(O), board.winner());
+        assert_eq!(~[3, 7, 11, 15], board.winning_line().unwrap());
+    }
+
+    #[test]
+    fn does_not_pause_by_default() {
+        let game = Game::new(create_fake_output(), Board::new(),
+                             create_human_with_input(~"0"), create_human_with_input(~"1"));
+
+        assert_eq!(0, game.thinking_delay);
+    }
+
+    #[test]
+    fn tells_both_players_how_the_game_ended() {
+        let board = Board::new_from_spaces(~['x','x',' ',
+                                             'o','o',' ',
+                                             ' ',' ',' ' ]);
+        let matchboxes = @mut Matchboxes::new();
+        let menace = Player::new_computer(@Menace::new(matchboxes) as @Strategy);
+        let fake_player1 = create_human_with_input(~"2");
+
+        matchboxes.choose_move(&board.with_to_move(O), &mut XorShiftRng::new_seeded(1, 2, 3, 4));
+
+        let (_, key, index) = matchboxes.history[0].clone();
+        assert_eq!(2, matchboxes.boxes.get(&key)[index]);
+
+        let game = Game::new(create_fake_output(), board, fake_player1, menace);
+        game.next_turn();
+
+        assert!(matchboxes.history.is_empty());
+        assert_eq!(1, matchboxes.boxes.get(&key)[index]);
+    }
+
+    #[test]
+    fn flashes_a_player_that_could_not_save_under_the_outcome() {
+        let board = Board::new_from_spaces(~['x','x',' ',
+                                             'o','o',' ',
+                                             ' ',' ',' ' ]);
+        let matchboxes = @mut Matchboxes::new();
+        matchboxes.path = Some(~"/nonexistent/directory/menace.tsv");
+
+        let menace = Player::new_computer(@Menace::new(matchboxes) as @Strategy);
+        let game = Game::new(create_fake_output(), board, create_human_with_input(~"2"), menace);
+
+        let message = game.next_turn().flash_message.unwrap();
+
+        assert!(message.starts_with("x wins!\n"));
+        assert!(message.len() > "x wins!\n".len());
+    }
+}
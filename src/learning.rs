@@ -0,0 +1,274 @@
+use std::hashmap::HashMap;
+use std::io;
+use std::rand::{Rng, XorShiftRng};
+
+use board::*;
+use notation::*;
+use symmetry::*;
+
+static UNKNOWN_VALUE: float = 0.5;
+
+// How likely the player who just moved is to win from each position, learned
+// by temporal difference. Symmetric positions share one entry.
+pub struct ValueTable {
+    values: HashMap<~str, float>
+}
+
+impl ValueTable {
+    pub fn new() -> ValueTable {
+        ValueTable { values: HashMap::new() }
+    }
+
+    pub fn len(&self) -> uint {
+        self.values.len()
+    }
+
+    // Finished positions are worth their result, so they are never stored.
+    pub fn value(&self, board: &Board) -> float {
+        let mover = board.current_token().other();
+
+        match board.outcome() {
+            Win { token: winner, _ } => if winner == mover { 1.0 } else { 0.0 },
+            Draw                     => 0.5,
+            InProgress               => match self.values.find(&key(board)) {
+                Some(&value) => value,
+                None         => UNKNOWN_VALUE
+            }
+        }
+    }
+
+    pub fn update(&mut self, board: &Board, target: float, learning_rate: float) {
+        let value = self.value(board);
+
+        self.values.insert(key(board), value + learning_rate * (target - value));
+    }
+
+    pub fn best_move(&self, board: &Board) -> Option<int> {
+        let mut best_index = None;
+        let mut best_value = -1.0;
+
+        for &index in board.available_spaces().iter() {
+            let value = self.value(&board.place(index));
+
+            if value > best_value {
+                best_index = Some(index);
+                best_value = value;
+            }
+        }
+
+        best_index
+    }
+
+    // One "notation<TAB>value" line per position.
+    pub fn to_tsv(&self) -> ~str {
+        let mut tsv = ~"";
+
+        for (notation, value) in self.values.iter() {
+            tsv.push_str(*notation + "\t" + value.to_str() + "\n");
+        }
+
+        tsv
+    }
+
+    pub fn from_tsv(tsv: &str) -> Result<ValueTable, ~str> {
+        let mut table = ValueTable::new();
+
+        for (line_number, line) in tsv.line_iter().enumerate() {
+            let fields: ~[&str] = line.split_iter('\t').collect();
+
+            if fields.len() != 2 || Board::from_notation(fields[0]).is_err() {
+                return Err(~"Line " + (line_number + 1).to_str() + " is not a position and a value.");
+            }
+
+            match from_str::<float>(fields[1]) {
+                Some(value) => { table.values.insert(fields[0].to_owned(), value); },
+                None        => return Err(~"Line " + (line_number + 1).to_str() + " has an invalid value.")
+            }
+        }
+
+        Ok(table)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), ~str> {
+        match io::file_writer(&Path(path), [io::Create, io::Truncate]) {
+            Ok(writer) => {
+                writer.write_str(self.to_tsv());
+                Ok(())
+            },
+
+            Err(error) => Err(error)
+        }
+    }
+
+    pub fn load(path: &str) -> Result<ValueTable, ~str> {
+        match io::read_whole_file_str(&Path(path)) {
+            Ok(tsv)    => ValueTable::from_tsv(tsv),
+            Err(error) => Err(error)
+        }
+    }
+}
+
+fn key(board: &Board) -> ~str {
+    let (canonical, _) = board.canonical();
+
+    canonical.to_notation()
+}
+
+// Trains a value table by playing it against itself, exploring a random move
+// with the given probability and otherwise playing its best move.
+pub struct Trainer {
+    table: ValueTable,
+    learning_rate: float,
+    exploration: float,
+    rng: XorShiftRng
+}
+
+impl Trainer {
+    pub fn new(learning_rate: float, exploration: float) -> Trainer {
+        Trainer::with_table(ValueTable::new(), learning_rate, exploration)
+    }
+
+    pub fn with_table(table: ValueTable, learning_rate: float, exploration: float) -> Trainer {
+        Trainer { table: table,
+                  learning_rate: learning_rate,
+                  exploration: exploration,
+                  rng: XorShiftRng::new() }
+    }
+
+    pub fn train(&mut self, episodes: uint) {
+        do episodes.times {
+            self.play_episode();
+        }
+    }
+
+    // Each player's previous position moves toward the value of the one it
+    // led to, and both last positions toward the final result.
+    pub fn play_episode(&mut self) {
+        let first = if self.rng.gen::<bool>() { X } else { O };
+        let mut board = Board::new().with_to_move(first);
+        let mut last_x: Option<Board> = None;
+        let mut last_o: Option<Board> = None;
+
+        while !board.is_game_over() {
+            let token = board.current_token();
+            board = board.place(self.choose_move(&board));
+
+            let previous = if token == X { last_x.clone() } else { last_o.clone() };
+            let target = self.table.value(&board);
+
+            match previous {
+                Some(previous) => self.table.update(&previous, target, self.learning_rate),
+                None           => ()
+            }
+
+            if token == X { last_x = Some(board.clone()) } else { last_o = Some(board.clone()) }
+        }
+
+        let waiting = if board.current_token() == X { last_x } else { last_o };
+        let target = 1.0 - self.table.value(&board);
+
+        match waiting {
+            Some(previous) => self.table.update(&previous, target, self.learning_rate),
+            None           => ()
+        }
+    }
+
+    fn choose_move(&mut self, board: &Board) -> int {
+        let spaces = board.available_spaces();
+
+        if self.rng.gen::<float>() < self.exploration {
+            spaces[self.rng.gen_integer_range(0u, spaces.len())]
+        } else {
+            self.table.best_move(board).unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use board::*;
+
+    #[test]
+    fn values_finished_positions_by_their_result() {
+        let table = ValueTable::new();
+        let x_won = Board::new_from_spaces(~['x','x','x',
+                                             'o','o',' ',
+                                             ' ',' ',' ' ]);
+
+        let draw = Board::new_from_spaces(~['o','x','o',
+                                            'o','x','x',
+                                            'x','o','x' ]);
+
+        assert_eq!(1.0, table.value(&x_won));
+        assert_eq!(0.0, table.value(&x_won.with_to_move(X)));
+        assert_eq!(0.5, table.value(&draw));
+        assert_eq!(0.5, table.value(&Board::new().place(4)));
+    }
+
+    #[test]
+    fn moves_values_toward_their_target() {
+        let mut table = ValueTable::new();
+        let board = Board::new().place(4);
+
+        table.update(&board, 1.0, 0.2);
+
+        assert_eq!(0.6, table.value(&board));
+        assert_eq!(1, table.len());
+    }
+
+    #[test]
+    fn shares_values_between_symmetric_positions() {
+        let mut table = ValueTable::new();
+
+        table.update(&Board::new().place(0), 1.0, 0.5);
+
+        assert_eq!(0.75, table.value(&Board::new().place(8)));
+        assert_eq!(1, table.len());
+    }
+
+    #[test]
+    fn plays_the_move_with_the_best_value() {
+        let mut table = ValueTable::new();
+        table.update(&Board::new().place(4), 1.0, 0.5);
+
+        let win_board = Board::new_from_spaces(~['x','x',' ',
+                                                 'o','o',' ',
+                                                 ' ',' ',' ' ]);
+
+        assert_eq!(Some(4), table.best_move(&Board::new()));
+        assert_eq!(Some(2), table.best_move(&win_board));
+    }
+
+    #[test]
+    fn round_trips_through_tab_separated_values() {
+        let mut table = ValueTable::new();
+        table.update(&Board::new().place(4), 1.0, 0.5);
+        table.update(&Board::new().place(0).place(4), 0.0, 0.5);
+
+        let loaded = ValueTable::from_tsv(table.to_tsv()).unwrap();
+
+        assert_eq!(2, loaded.len());
+        assert_eq!(0.75, loaded.value(&Board::new().place(4)));
+        assert_eq!(0.25, loaded.value(&Board::new().place(2).place(4)));
+    }
+
+    #[test]
+    fn rejects_malformed_value_files() {
+        assert_eq!(Err(~"Line 1 is not a position and a value."), ValueTable::from_tsv("..x/.../... o"));
+        assert_eq!(Err(~"Line 2 has an invalid value."), ValueTable::from_tsv(".../.../... x\t0.5\n..x/.../... o\thigh"));
+    }
+
+    #[test]
+    fn training_keeps_values_between_a_loss_and_a_win() {
+        let mut trainer = Trainer::new(0.2, 0.1);
+
+        trainer.train(200);
+
+        assert!(trainer.table.len() > 0);
+
+        for (_, &value) in trainer.table.values.iter() {
+            assert!(value >= 0.0 && value <= 1.0);
+        }
+    }
+}
@@ -1,48 +1,297 @@
+extern mod extra;
+
+use std::hashmap::HashMap;
 use std::io;
 use ai::*;
+use analysis::*;
+use bitboard::*;
 use board::*;
+use book::*;
 use console_input::*;
 use console_output::*;
 use console_reader::*;
 use console_writer::*;
+use difficulty::*;
+use evaluator::*;
 use game::*;
+use learning::*;
+use mcts::*;
+use menace::*;
 use menu::*;
+use notation::*;
 use player::*;
+use rules::*;
+use strategy::*;
+use symbols::*;
+use symmetry::*;
+use tablebase::*;
+use transposition::*;
+use validation::*;
 
 mod ai;
+mod analysis;
+mod bitboard;
 mod board;
+mod book;
 mod console_input;
 mod console_output;
 mod console_reader;
 mod console_writer;
+mod difficulty;
+mod evaluator;
 mod game;
+mod learning;
+mod mcts;
+mod menace;
 mod menu;
+mod notation;
 mod player;
+mod rules;
+mod strategy;
+mod symbols;
+mod symmetry;
+mod tablebase;
+mod transposition;
+mod validation;
+
+static VALUES_FILE: &'static str = "values.tsv";
+
+static BOOK_FILE: &'static str = "book.tsv";
+
+static SELF_PLAY_ITERATIONS: uint = 1000;
+
+static TRAIN_USAGE: &'static str = "Usage: tictactoe train <episodes> <learning rate> <exploration> [file]";
+
+static HINTS_USAGE: &'static str = "Usage: tictactoe --hints <hints per player>";
+
+static STRATEGY_USAGE: &'static str = "Usage: tictactoe --strategy <name>, with a name from --list";
+
+static SEED_USAGE: &'static str = "Usage: tictactoe --seed <number>";
+
+static SIZE_USAGE: &'static str = "Usage: tictactoe --size <spaces along each side>";
+
+static WIN_LENGTH_USAGE: &'static str = "Usage: tictactoe --win-length <tokens in a row>, no more than the size";
+
+static OPTIONS: [&'static str, ..8] = ["--size", "--win-length", "--seed", "--book", "--hints", "--strategy", "--values", "--menace"];
+
+static USAGE: &'static str = "Usage: tictactoe [--size <number>] [--win-length <number>] [--seed <number>] [--book <file>] [--hints <hints per player>] [--list]\n                 [--strategy <name> | --values <file> | --menace <file>]";
+
+static BOOK_USAGE: &'static str = "Usage: tictactoe book selfplay <games> <size> <win length> <plies> [file]\n       tictactoe book record <games file> <size> <win length> <plies> [file]";
 
 fn main() {
-    loop {
-        let (menu, board, input, output) = setup();
-        let (player1, player2) = setup_players(menu, input.clone(), output);
+    let args = std::os::args();
+
+    if args.len() > 1 && args[1] == ~"train" {
+        train(args.slice_from(2));
+    } else if args.len() > 1 && args[1] == ~"book" {
+        build_book(args.slice_from(2));
+    } else {
+        play_with_options(args.slice_from(1));
+    }
+}
 
-        let mut game = Game::new(output, board, player1, player2);
+// Every option but --list takes a value, and they combine in any order. The
+// seed is applied before the book and the computer players are set up, so
+// they all share it.
+fn play_with_options(args: &[~str]) {
+    let mut options = HashMap::new();
+    let mut i = 0;
 
-        loop {
-            game.board = game.next_turn().clone();
+    while i < args.len() {
+        if args[i] == ~"--list" {
+            options.insert(args[i].clone(), ~"");
+            i += 1;
+        } else if i + 1 < args.len() && OPTIONS.iter().any( |option: &&str| *option == args[i].as_slice() ) {
+            options.insert(args[i].clone(), args[i + 1].clone());
+            i += 2;
+        } else {
+            return println(USAGE);
+        }
+    }
 
-            if game.board.is_game_over() {
-                game.output.clear_screen();
-                game.output.print_board(game.board.clone());
-                std::rt::io::timer::sleep(3000);
+    let size = match options.find(&~"--size") {
+        Some(size) => match from_str::<uint>(*size) {
+            Some(size) if size > 0 => size,
+            _                      => return println(SIZE_USAGE)
+        },
 
-                break
-            }
+        None => 3
+    };
+
+    let win_length = match options.find(&~"--win-length") {
+        Some(win_length) => match from_str::<uint>(*win_length) {
+            Some(win_length) => win_length,
+            None             => return println(WIN_LENGTH_USAGE)
+        },
+
+        None => size
+    };
+
+    let start = Board::with_rules(size, win_length);
+
+    if start.check_position().is_some() {
+        return println(WIN_LENGTH_USAGE);
+    }
+
+    let registry = match options.find(&~"--seed") {
+        Some(seed) => match from_str::<u32>(*seed) {
+            Some(seed) => Registry::with_random_ties(seed),
+            None       => return println(SEED_USAGE)
+        },
+
+        None => Registry::with_builtins()
+    };
+
+    let registry = match options.find(&~"--book") {
+        Some(path) => match OpeningBook::load(*path) {
+            Ok(book)   => registry.with_book(@book),
+            Err(error) => return println(error)
+        },
+
+        None => registry
+    };
+
+    if options.contains_key(&~"--list") {
+        for name in registry.names().iter() {
+            println(*name);
         }
+
+        return;
     }
+
+    let hints = match options.find(&~"--hints") {
+        Some(hints) => match from_str::<uint>(*hints) {
+            Some(hints) => hints,
+            None        => return println(HINTS_USAGE)
+        },
+
+        None => DEFAULT_HINTS
+    };
+
+    let strategy = match options.find(&~"--strategy") {
+        Some(name) => match registry.find(*name) {
+            Some(strategy) => Some(strategy),
+            None           => return println(STRATEGY_USAGE)
+        },
+
+        None => None
+    };
+
+    let learned = match options.find(&~"--values") {
+        Some(path) => match ValueTable::load(*path) {
+            Ok(table)  => Some(@Learned::new(@table) as @Strategy),
+            Err(error) => return println(error)
+        },
+
+        None => None
+    };
+
+    let menace = match options.find(&~"--menace") {
+        Some(path) => match Matchboxes::open(*path) {
+            Ok(matchboxes) => Some(@Menace::with_ai(registry.engine(), @mut matchboxes) as @Strategy),
+            Err(error)     => return println(error)
+        },
+
+        None => None
+    };
+
+    play(&start, &registry, strategy.or(learned).or(menace), hints);
 }
 
-fn setup() -> (Menu, Board, ConsoleInput, ConsoleOutput) {
-    let board = Board::new();
+fn train(args: &[~str]) {
+    if args.len() < 3 {
+        return println(TRAIN_USAGE);
+    }
+
+    let path = if args.len() > 3 { args[3].clone() } else { VALUES_FILE.to_owned() };
 
+    match (from_str::<uint>(args[0]), from_str::<float>(args[1]), from_str::<float>(args[2])) {
+        (Some(episodes), Some(learning_rate), Some(exploration)) => {
+            let mut trainer = Trainer::new(learning_rate, exploration);
+            trainer.train(episodes);
+
+            match trainer.table.save(path) {
+                Ok(())     => println("Learned " + trainer.table.len().to_str() + " positions into " + path + "."),
+                Err(error) => println(error)
+            }
+        },
+
+        _ => println(TRAIN_USAGE)
+    }
+}
+
+// Books are built from self-play by Monte Carlo search, which picks among
+// good moves at random, or from a file of recorded games.
+fn build_book(args: &[~str]) {
+    if args.len() < 5 {
+        return println(BOOK_USAGE);
+    }
+
+    let path = if args.len() > 5 { args[5].clone() } else { BOOK_FILE.to_owned() };
+
+    let (start, plies) = match (from_str::<uint>(args[2]), from_str::<uint>(args[3]), from_str::<uint>(args[4])) {
+        (Some(dimension), Some(win_length), Some(plies)) => (Board::with_rules(dimension, win_length), plies),
+        _                                                => return println(BOOK_USAGE)
+    };
+
+    if start.check_position().is_some() {
+        return println(BOOK_USAGE);
+    }
+
+    let book = match (args[0].as_slice(), from_str::<uint>(args[1])) {
+        ("selfplay", Some(games)) => {
+            let strategy = @MonteCarlo::new(Iterations(SELF_PLAY_ITERATIONS), DEFAULT_EXPLORATION) as @Strategy;
+            Ok(OpeningBook::from_self_play(&start, strategy, games, plies))
+        },
+
+        ("record", _) => match io::read_whole_file_str(&Path(args[1])) {
+            Ok(games)  => OpeningBook::from_recorded_games(&start, games, plies),
+            Err(error) => Err(error)
+        },
+
+        _ => return println(BOOK_USAGE)
+    };
+
+    match book {
+        Ok(book)   => match book.save(path) {
+            Ok(())     => println("Wrote " + book.len().to_str() + " positions to " + path + "."),
+            Err(error) => println(error)
+        },
+
+        Err(error) => println(error)
+    }
+}
+
+// Every game starts from the empty `start` board. Every computer player uses
+// `computer` when it is given, instead of asking for one from the registry.
+// Each human can ask for `hints` hints a game. The players pick who moves
+// first in the first game, and it alternates after.
+fn play(start: &Board, registry: &Registry, computer: Option<@Strategy>, hints: uint) {
+    let mut first_player = None;
+
+    loop {
+        let (menu, input, output) = setup();
+
+        let token = match first_player {
+            Some(token) => token.other(),
+            None        => setup_first_player(&menu, &output)
+        };
+
+        first_player = Some(token);
+
+        let board = start.with_to_move(token);
+        let (player1, player2) = setup_players(menu, input.clone(), output, registry, computer, hints);
+
+        let mut game = Game::new(output, board, player1, player2).with_thinking_delay(1000);
+        let final_board = game.play_out();
+
+        game.output.clear_screen();
+        game.output.print_board(final_board);
+        std::rt::io::timer::sleep(3000);
+    }
+}
+
+fn setup() -> (Menu, ConsoleInput, ConsoleOutput) {
     let reader = RealReader(io::stdin());
     let input = ConsoleInput::new(reader.clone());
 
@@ -50,37 +299,63 @@ fn setup() -> (Menu, Board, ConsoleInput, ConsoleOutput) {
 
     let output = ConsoleOutput::new(@RealWriter);
 
-    (menu, board, input.clone(), output)
+    (menu, input.clone(), output)
+}
+
+fn setup_first_player(menu: &Menu, output: &ConsoleOutput) -> Token {
+    output.clear_screen();
+    output.print_first_player_menu();
+
+    match menu.get_first_player_choice() {
+        Some(token) => token,
+        None        => setup_first_player(menu, output)
+    }
 }
 
-fn setup_players(menu: Menu, input: ConsoleInput, output: ConsoleOutput) -> (Player, Player) {
+fn setup_players(menu: Menu, input: ConsoleInput, output: ConsoleOutput, registry: &Registry,
+                 computer: Option<@Strategy>, hints: uint) -> (Player, Player) {
     output.clear_screen();
     output.print_menu();
 
     match menu.get_menu_choice() {
-        Some(choice) => match_choice(choice, input),
-        None         => setup_players(menu, input, output)
+        Some(choice) => match_choice(choice, &menu, input, &output, registry, computer, hints),
+        None         => setup_players(menu, input, output, registry, computer, hints)
     }
 }
 
-fn match_choice(choice: int, input: ConsoleInput) -> (Player, Player) {
-    let ai = AI::new(Minimax);
+fn match_choice(choice: int, menu: &Menu, input: ConsoleInput, output: &ConsoleOutput, registry: &Registry,
+                computer: Option<@Strategy>, hints: uint) -> (Player, Player) {
+    let human = || Player::new_human_with_hints(input.clone(), hints);
 
     match choice {
-        1 => (HumanPlayer { input: input.clone() },
-              HumanPlayer { input: input.clone() }),
+        1 => (human(), human()),
+
+        2 => (human(),
+              setup_computer(menu, output, O, registry, computer)),
+
+        3 => (setup_computer(menu, output, X, registry, computer),
+              human()),
 
-        2 => (HumanPlayer { input: input.clone() },
-              ComputerPlayer { ai: ai.clone() }),
+        4 => (setup_computer(menu, output, X, registry, computer),
+              setup_computer(menu, output, O, registry, computer)),
 
-        3 => (ComputerPlayer { ai: ai.clone() },
-              HumanPlayer { input: input.clone() }),
+        _ => (human(), human())
+    }
+}
 
-        4 => (ComputerPlayer { ai: ai.clone() },
-              ComputerPlayer { ai: ai.clone() }),
+fn setup_computer(menu: &Menu, output: &ConsoleOutput, token: Token, registry: &Registry,
+                  computer: Option<@Strategy>) -> Player {
+    match computer {
+        Some(strategy) => return Player::new_computer(strategy),
+        None           => ()
+    }
+
+    output.clear_screen();
+    output.print_computer_menu(token, registry.names());
 
-        _ => (HumanPlayer { input: input.clone() },
-              HumanPlayer { input: input.clone() })
+    match menu.get_computer_choice(registry) {
+        Some(strategy) => Player::new_computer(strategy),
+        None           => setup_computer(menu, output, token, registry, computer)
     }
 }
 
@@ -0,0 +1,253 @@
+use std::num::{ln, sqrt};
+use std::rand::{Rng, XorShiftRng};
+use extra::time::precise_time_ns;
+
+use bitboard::*;
+use board::*;
+
+pub static DEFAULT_EXPLORATION: float = 1.41421356;
+
+#[deriving(Clone, Eq)]
+pub enum Budget {
+    Iterations(uint),
+    Milliseconds(u64)
+}
+
+struct Node {
+    board: Bitboard,
+    last_move: Option<int>,
+    parent: Option<uint>,
+    children: ~[uint],
+    untried: ~[int],
+    visits: uint,
+    reward: float
+}
+
+impl Node {
+    fn new(board: Bitboard, last_move: Option<int>, parent: Option<uint>) -> Node {
+        let untried = if board.is_game_over() { ~[] } else { board.available_spaces() };
+
+        Node { board: board,
+               last_move: last_move,
+               parent: parent,
+               children: ~[],
+               untried: untried,
+               visits: 0,
+               reward: 0.0 }
+    }
+}
+
+// A Monte Carlo search tree grown with UCT. Each node's reward is counted
+// for the player who made the move leading to it.
+pub struct SearchTree {
+    nodes: ~[Node],
+    exploration: float
+}
+
+impl SearchTree {
+    pub fn new(board: Bitboard, exploration: float) -> SearchTree {
+        SearchTree { nodes: ~[Node::new(board, None, None)],
+                     exploration: exploration }
+    }
+
+    // Runs at least one iteration, so there is always a move to play.
+    pub fn search(board: Bitboard, budget: Budget, exploration: float, rng: &mut XorShiftRng) -> Option<int> {
+        let mut tree = SearchTree::new(board, exploration);
+
+        tree.iterate(rng);
+
+        match budget {
+            Iterations(count) => {
+                for _ in range(1, count) {
+                    tree.iterate(rng);
+                }
+            },
+
+            Milliseconds(budget) => {
+                let deadline = precise_time_ns() + budget * 1000000;
+
+                while precise_time_ns() < deadline {
+                    tree.iterate(rng);
+                }
+            }
+        }
+
+        tree.most_visited_move()
+    }
+
+    pub fn iterate(&mut self, rng: &mut XorShiftRng) {
+        let leaf = self.select();
+        let node = self.expand(leaf, rng);
+        let winner = self.playout(node, rng);
+
+        self.backpropagate(node, winner);
+    }
+
+    pub fn visits(&self) -> uint {
+        self.nodes[0].visits
+    }
+
+    pub fn most_visited_move(&self) -> Option<int> {
+        let mut best_move = None;
+        let mut most_visits = 0;
+
+        for &child in self.nodes[0].children.iter() {
+            if self.nodes[child].visits > most_visits {
+                most_visits = self.nodes[child].visits;
+                best_move = self.nodes[child].last_move;
+            }
+        }
+
+        best_move
+    }
+
+    fn select(&self) -> uint {
+        let mut index = 0;
+
+        while self.nodes[index].untried.is_empty() && !self.nodes[index].children.is_empty() {
+            index = self.best_child(index);
+        }
+
+        index
+    }
+
+    fn best_child(&self, index: uint) -> uint {
+        let parent = &self.nodes[index];
+        let mut best_child = parent.children[0];
+        let mut best_value = -1.0;
+
+        for &child in parent.children.iter() {
+            let value = self.upper_confidence_bound(child, parent.visits);
+
+            if value > best_value {
+                best_child = child;
+                best_value = value;
+            }
+        }
+
+        best_child
+    }
+
+    fn upper_confidence_bound(&self, index: uint, parent_visits: uint) -> float {
+        let node = &self.nodes[index];
+        let visits = node.visits as float;
+
+        node.reward / visits + self.exploration * sqrt(ln(parent_visits as float) / visits)
+    }
+
+    // Finished positions have nothing left to try and are played out as they are.
+    fn expand(&mut self, index: uint, rng: &mut XorShiftRng) -> uint {
+        if self.nodes[index].untried.is_empty() {
+            return index;
+        }
+
+        let choice = rng.gen_integer_range(0u, self.nodes[index].untried.len());
+        let move = self.nodes[index].untried.swap_remove(choice);
+        let board = self.nodes[index].board.place(move);
+        let child = self.nodes.len();
+
+        self.nodes.push(Node::new(board, Some(move), Some(index)));
+        self.nodes[index].children.push(child);
+
+        child
+    }
+
+    fn playout(&self, index: uint, rng: &mut XorShiftRng) -> Option<Token> {
+        let mut board = self.nodes[index].board.clone();
+
+        while !board.is_game_over() {
+            let spaces = board.available_spaces();
+            board = board.place(spaces[rng.gen_integer_range(0u, spaces.len())]);
+        }
+
+        board.winner()
+    }
+
+    fn backpropagate(&mut self, index: uint, winner: Option<Token>) {
+        let mut current = Some(index);
+
+        loop {
+            let index = match current {
+                Some(index) => index,
+                None        => break
+            };
+
+            let mover = self.nodes[index].board.current_token().other();
+
+            self.nodes[index].visits += 1;
+            self.nodes[index].reward += match winner {
+                Some(token) if token == mover => 1.0,
+                Some(*)                       => 0.0,
+                None                          => 0.5
+            };
+
+            current = self.nodes[index].parent;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bitboard::*;
+    use board::*;
+    use std::rand::XorShiftRng;
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::new_seeded(1, 2, 3, 4)
+    }
+
+    fn search(board: Board, iterations: uint) -> Option<int> {
+        SearchTree::search(Bitboard::from_board(&board), Iterations(iterations), DEFAULT_EXPLORATION, &mut rng())
+    }
+
+    #[test]
+    fn tries_every_move_at_the_root() {
+        let mut tree = SearchTree::new(Bitboard::from_board(&Board::new()), DEFAULT_EXPLORATION);
+        let mut rng = rng();
+
+        do 100.times {
+            tree.iterate(&mut rng);
+        }
+
+        assert_eq!(100, tree.visits());
+        assert_eq!(9, tree.nodes[0].children.len());
+    }
+
+    #[test]
+    fn takes_a_win() {
+        let board = Board::new_from_spaces(~[' ','o','x',
+                                             ' ','o','x',
+                                             ' ',' ',' ' ]);
+
+        assert_eq!(Some(8), search(board, 1000));
+    }
+
+    #[test]
+    fn blocks_a_loss() {
+        let board = Board::new_from_spaces(~['x',' ',' ',
+                                             'o','o',' ',
+                                             ' ',' ','x' ]);
+
+        assert_eq!(Some(5), search(board, 3000));
+    }
+
+    #[test]
+    fn plays_boards_too_large_for_minimax() {
+        let board = Board::with_rules(7, 4).place(24).place(25).place(17).place(18).place(10).place(11);
+        let budget = Milliseconds(200);
+
+        let index = SearchTree::search(Bitboard::from_board(&board), budget, DEFAULT_EXPLORATION, &mut rng());
+
+        assert!(index == Some(3) || index == Some(31));
+    }
+
+    #[test]
+    fn has_no_move_when_the_game_is_over() {
+        let full_board = Board::new_from_spaces(~['o','x','o',
+                                                  'o','x','x',
+                                                  'x','o','x' ]);
+
+        assert_eq!(None, search(full_board, 10));
+    }
+}
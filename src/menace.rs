@@ -0,0 +1,275 @@
+use std::hashmap::HashMap;
+use std::io;
+use std::os;
+use std::rand::{Rng, XorShiftRng};
+use std::util;
+use std::vec::from_elem;
+
+use board::*;
+use notation::*;
+use symmetry::*;
+
+// Donald Michie's MENACE: a matchbox of beads for every position it has
+// faced, one colour per move. It draws a bead to move, then adds beads for
+// the moves of a game it won or drew and takes them away after a loss.
+pub struct Matchboxes {
+    boxes: HashMap<~str, ~[uint]>,
+    history: ~[(Token, ~str, int)],
+    path: Option<~str>
+}
+
+impl Matchboxes {
+    pub fn new() -> Matchboxes {
+        Matchboxes { boxes: HashMap::new(), history: ~[], path: None }
+    }
+
+    // Starts with empty boxes when the file doesn't exist yet.
+    pub fn open(path: &str) -> Result<Matchboxes, ~str> {
+        let mut matchboxes = if os::path_exists(&Path(path)) {
+            match io::read_whole_file_str(&Path(path)) {
+                Ok(tsv)    => match Matchboxes::from_tsv(tsv) {
+                    Ok(matchboxes) => matchboxes,
+                    Err(error)     => return Err(error)
+                },
+
+                Err(error) => return Err(error)
+            }
+        } else {
+            Matchboxes::new()
+        };
+
+        matchboxes.path = Some(path.to_owned());
+
+        Ok(matchboxes)
+    }
+
+    pub fn len(&self) -> uint {
+        self.boxes.len()
+    }
+
+    pub fn choose_move(&mut self, board: &Board, rng: &mut XorShiftRng) -> Option<int> {
+        if board.is_game_over() {
+            return None;
+        }
+
+        let (canonical, transform) = board.canonical();
+        let key = canonical.to_notation();
+        let beads = self.beads(&canonical);
+        let mut draw = rng.gen_integer_range(0u, beads.iter().fold(0u, |total: uint, &count: &uint| total + count));
+        let mut chosen = 0;
+
+        for (index, &count) in beads.iter().enumerate() {
+            if draw < count {
+                chosen = index as int;
+                break;
+            }
+
+            draw -= count;
+        }
+
+        self.history.push((board.current_token(), key, chosen));
+
+        Some(transform.apply(chosen, canonical.dimension()))
+    }
+
+    // Refills a box that has run out of beads, as the original did by
+    // starting the position over.
+    fn beads(&mut self, canonical: &Board) -> ~[uint] {
+        let key = canonical.to_notation();
+        let is_empty = match self.boxes.find(&key) {
+            Some(beads) => beads.iter().all( |&count: &uint| count == 0 ),
+            None        => true
+        };
+
+        if is_empty {
+            self.boxes.insert(key.clone(), starting_beads(canonical));
+        }
+
+        self.boxes.get(&key).clone()
+    }
+
+    pub fn learn(&mut self, outcome: &GameOutcome, token: Token) {
+        let change = match *outcome {
+            Win { token: winner, _ } if winner == token => 3,
+            Win { _ }                                   => -1,
+            Draw                                        => 1,
+            InProgress                                  => return
+        };
+
+        let history = util::replace(&mut self.history, ~[]);
+
+        for (mover, key, index) in history.move_iter() {
+            if mover != token {
+                self.history.push((mover, key, index));
+            } else {
+                match self.boxes.find_mut(&key) {
+                    Some(beads) => beads[index] = (beads[index] as int + change).max(&0) as uint,
+                    None        => ()
+                }
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ~str> {
+        match self.path {
+            Some(ref path) => match io::file_writer(&Path(*path), [io::Create, io::Truncate]) {
+                Ok(writer) => {
+                    writer.write_str(self.to_tsv());
+                    Ok(())
+                },
+
+                Err(error) => Err(error)
+            },
+
+            None => Ok(())
+        }
+    }
+
+    // One "notation<TAB>beads" line per box, the bead counts separated by commas.
+    pub fn to_tsv(&self) -> ~str {
+        let mut tsv = ~"";
+
+        for (notation, beads) in self.boxes.iter() {
+            let counts = beads.map( |count: &uint| count.to_str() );
+
+            tsv.push_str(*notation + "\t" + counts.connect(",") + "\n");
+        }
+
+        tsv
+    }
+
+    pub fn from_tsv(tsv: &str) -> Result<Matchboxes, ~str> {
+        let mut matchboxes = Matchboxes::new();
+
+        for (line_number, line) in tsv.line_iter().enumerate() {
+            let fields: ~[&str] = line.split_iter('\t').collect();
+            let error = ~"Line " + (line_number + 1).to_str() + " is not a position and its beads.";
+
+            if fields.len() != 2 {
+                return Err(error);
+            }
+
+            let board = match Board::from_notation(fields[0]) {
+                Ok(board) => board,
+                Err(*)    => return Err(error)
+            };
+
+            let counts: ~[Option<uint>] = fields[1].split_iter(',').map( |count: &str| from_str::<uint>(count) ).collect();
+
+            if counts.len() != board.spaces.len() || counts.iter().any( |count: &Option<uint>| count.is_none() ) {
+                return Err(error);
+            }
+
+            matchboxes.boxes.insert(fields[0].to_owned(), counts.move_iter().map( |count: Option<uint>| count.unwrap() ).collect());
+        }
+
+        Ok(matchboxes)
+    }
+}
+
+// Fewer beads for later moves, so the last moves of a game learn fastest.
+// Moves that are symmetric to another one get none.
+fn starting_beads(canonical: &Board) -> ~[uint] {
+    let available = canonical.available_spaces().len();
+    let count = if available > 2 { (available - 1) / 2 } else { 1 };
+    let mut beads = from_elem(canonical.spaces.len(), 0u);
+
+    for &index in canonical.distinct_moves().iter() {
+        beads[index] = count;
+    }
+
+    beads
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use board::*;
+    use std::rand::XorShiftRng;
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::new_seeded(1, 2, 3, 4)
+    }
+
+    #[test]
+    fn fills_new_boxes_with_a_bead_for_each_distinct_move() {
+        let mut matchboxes = Matchboxes::new();
+
+        assert_eq!(~[4, 4, 0, 0, 4, 0, 0, 0, 0], matchboxes.beads(&Board::new()));
+        assert_eq!(~[0, 0, 1, 0, 0, 0, 1, 1, 1], matchboxes.beads(&Board::from_notation("xo./oxx/... o").unwrap()));
+    }
+
+    #[test]
+    fn only_plays_moves_it_has_beads_for() {
+        let mut matchboxes = Matchboxes::new();
+        let mut rng = rng();
+
+        do 20.times {
+            let index = matchboxes.choose_move(&Board::new(), &mut rng).unwrap();
+
+            assert!(index == 0 || index == 1 || index == 4);
+        }
+    }
+
+    #[test]
+    fn plays_symmetric_positions_from_the_same_box() {
+        let mut matchboxes = Matchboxes::new();
+        let mut rng = rng();
+
+        let index = matchboxes.choose_move(&Board::new().place(8), &mut rng).unwrap();
+        matchboxes.choose_move(&Board::new().place(0), &mut rng);
+
+        assert_eq!(1, matchboxes.len());
+        assert!(Board::new().place(8).available_spaces().contains(&index));
+    }
+
+    #[test]
+    fn adds_beads_after_a_win_and_removes_them_after_a_loss() {
+        let mut matchboxes = Matchboxes::new();
+        let mut rng = rng();
+        let board = Board::new();
+
+        let index = matchboxes.choose_move(&board, &mut rng).unwrap();
+        matchboxes.learn(&Win { token: X, line: ~[] }, X);
+
+        assert_eq!(7, matchboxes.beads(&board)[index]);
+
+        let index = matchboxes.choose_move(&board, &mut rng).unwrap();
+        matchboxes.learn(&Win { token: O, line: ~[] }, X);
+
+        assert!(matchboxes.beads(&board)[index] == 3 || matchboxes.beads(&board)[index] == 6);
+    }
+
+    #[test]
+    fn learns_only_from_the_moves_of_the_token_it_played() {
+        let mut matchboxes = Matchboxes::new();
+        let mut rng = rng();
+        let board = Board::new();
+
+        matchboxes.choose_move(&board, &mut rng);
+        matchboxes.choose_move(&board.place(4), &mut rng);
+        matchboxes.learn(&Draw, O);
+
+        assert_eq!(1, matchboxes.history.len());
+        assert_eq!(12, matchboxes.beads(&board).iter().fold(0u, |total: uint, &count: &uint| total + count));
+    }
+
+    #[test]
+    fn round_trips_through_tab_separated_values() {
+        let mut matchboxes = Matchboxes::new();
+        let mut rng = rng();
+
+        matchboxes.choose_move(&Board::new(), &mut rng);
+        matchboxes.learn(&Draw, X);
+
+        let loaded = Matchboxes::from_tsv(matchboxes.to_tsv()).unwrap();
+
+        assert_eq!(matchboxes.boxes, loaded.boxes);
+    }
+
+    #[test]
+    fn rejects_malformed_bead_files() {
+        assert_eq!(Err(~"Line 1 is not a position and its beads."), Matchboxes::from_tsv(".../.../... x\t1,2,3").map( |_| () ));
+        assert_eq!(Err(~"Line 1 is not a position and its beads."), Matchboxes::from_tsv("xxx x\t1,2,3").map( |_| () ));
+    }
+}
@@ -1,4 +1,6 @@
+use board::*;
 use console_input::*;
+use strategy::*;
 
 condition! {
     pub quit_choice: ~str -> int;
@@ -22,6 +24,21 @@ impl Menu {
         }
     }
 
+    pub fn get_computer_choice(&self, registry: &Registry) -> Option<@Strategy> {
+        match self.input.get_int() {
+            Some(choice) => registry.from_choice(choice),
+            None         => None
+        }
+    }
+
+    pub fn get_first_player_choice(&self) -> Option<Token> {
+        match self.input.get_int() {
+            Some(1) => Some(X),
+            Some(2) => Some(O),
+            _       => None
+        }
+    }
+
     fn validate_choice(&self, choice: int) -> Option<int> {
         match choice {
             0..4 => Some(choice),
@@ -34,8 +51,10 @@ impl Menu {
 #[cfg(test)]
 mod test {
     use super::*;
+    use board::*;
     use console_input::*;
     use console_reader::*;
+    use strategy::*;
 
     fn create_menu_with_mock(fake_input: ~str) -> Menu {
         let mock_reader = MockReader { str_in_stdin: fake_input };
@@ -62,5 +81,21 @@ mod test {
             assert_eq!(Some(-1), choice);
         }
     }
-}
 
+    #[test]
+    fn gets_a_computer_choice() {
+        let registry = Registry::with_builtins();
+        let menu = create_menu_with_mock(~"3\n");
+        let menu_with_invalid_choice = create_menu_with_mock(~"12\n");
+
+        assert_eq!(~"Medium", menu.get_computer_choice(&registry).unwrap().name());
+        assert!(menu_with_invalid_choice.get_computer_choice(&registry).is_none());
+    }
+
+    #[test]
+    fn gets_the_first_player_choice() {
+        assert_eq!(Some(X), create_menu_with_mock(~"1\n").get_first_player_choice());
+        assert_eq!(Some(O), create_menu_with_mock(~"2\n").get_first_player_choice());
+        assert_eq!(None, create_menu_with_mock(~"3\n").get_first_player_choice());
+    }
+}
@@ -0,0 +1,187 @@
+use board::*;
+use validation::*;
+
+// A position is written as its rows separated by '/', using 'x', 'o' and '.'
+// for the spaces, followed by the side to move and, when it differs from the
+// board's dimension, the win length: "xo./.x./..o x" or "..../..../..../.... o 3".
+
+#[deriving(Clone, Eq)]
+pub enum NotationError {
+    MissingSpaces,
+    MissingSideToMove,
+    WrongRowLength(uint, uint),
+    UnknownSpace(char),
+    UnknownSideToMove(~str),
+    InvalidWinLength(~str),
+    UnexpectedField(~str),
+    IllegalPosition(PositionError)
+}
+
+impl Board {
+    pub fn from_notation(notation: &str) -> Result<Board, NotationError> {
+        let fields: ~[&str] = notation.word_iter().collect();
+
+        match fields.len() {
+            0 => return Err(MissingSpaces),
+            1 => return Err(MissingSideToMove),
+            2 | 3 => (),
+            _ => return Err(UnexpectedField(fields[3].to_owned()))
+        }
+
+        let spaces = match parse_spaces(fields[0]) {
+            Ok(spaces) => spaces,
+            Err(error) => return Err(error)
+        };
+
+        let to_move = match fields[1] {
+            "x" => X,
+            "o" => O,
+            _   => return Err(UnknownSideToMove(fields[1].to_owned()))
+        };
+
+        let mut board = Board::new_from_cells(spaces).with_to_move(to_move);
+
+        if fields.len() == 3 {
+            match from_str::<uint>(fields[2]) {
+                Some(win_length) if win_length > 0 && win_length <= board.dimension() =>
+                    board = board.with_win_length(win_length),
+
+                _ => return Err(InvalidWinLength(fields[2].to_owned()))
+            }
+        }
+
+        match board.check_position() {
+            Some(error) => Err(IllegalPosition(error)),
+            None        => Ok(board)
+        }
+    }
+
+    pub fn to_notation(&self) -> ~str {
+        let dim = self.dimension();
+        let mut notation = ~"";
+
+        for (i, &cell) in self.spaces.iter().enumerate() {
+            if i > 0 && i % dim == 0 {
+                notation.push_char('/');
+            }
+
+            notation.push_char(cell_to_notation(cell));
+        }
+
+        notation.push_char(' ');
+        notation.push_char(token_to_notation(self.current_token()));
+
+        if self.win_length != dim {
+            notation.push_str(" " + self.win_length.to_str());
+        }
+
+        notation
+    }
+}
+
+fn parse_spaces(field: &str) -> Result<~[Cell], NotationError> {
+    let rows: ~[&str] = field.split_iter('/').collect();
+    let dimension = rows.len();
+    let mut spaces = ~[];
+
+    for (row_number, row) in rows.iter().enumerate() {
+        if row.char_len() != dimension {
+            return Err(WrongRowLength(row_number, row.char_len()));
+        }
+
+        for symbol in row.iter() {
+            match notation_to_cell(symbol) {
+                Some(cell) => spaces.push(cell),
+                None       => return Err(UnknownSpace(symbol))
+            }
+        }
+    }
+
+    Ok(spaces)
+}
+
+fn notation_to_cell(symbol: char) -> Option<Cell> {
+    match symbol {
+        'x' => Some(Filled(X)),
+        'o' => Some(Filled(O)),
+        '.' => Some(Empty),
+        _   => None
+    }
+}
+
+fn cell_to_notation(cell: Cell) -> char {
+    match cell {
+        Empty         => '.',
+        Filled(token) => token_to_notation(token)
+    }
+}
+
+fn token_to_notation(token: Token) -> char {
+    match token {
+        X => 'x',
+        O => 'o'
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use board::*;
+    use validation::*;
+
+    #[test]
+    fn writes_a_board_as_notation() {
+        let board = Board::new_from_spaces(~['x','o',' ',
+                                             ' ','x',' ',
+                                             ' ',' ','o' ]);
+
+        assert_eq!(~"xo./.x./..o x", board.to_notation());
+        assert_eq!(~"..../..../..../.... o 3", Board::with_rules(4, 3).with_to_move(O).to_notation());
+    }
+
+    #[test]
+    fn reads_a_board_from_notation() {
+        let board = Board::from_notation("xo./.x./..o x").unwrap();
+
+        let expected = Board::new_from_spaces(~['x','o',' ',
+                                                ' ','x',' ',
+                                                ' ',' ','o' ]);
+
+        assert_eq!(expected, board);
+    }
+
+    #[test]
+    fn reads_the_side_to_move_and_win_length() {
+        let board = Board::from_notation("x..../...../..o../...../..... o 4").unwrap();
+
+        assert_eq!(5, board.dimension());
+        assert_eq!(4, board.win_length);
+        assert_eq!(O, board.current_token());
+    }
+
+    #[test]
+    fn round_trips_through_notation() {
+        let notations = [".../.../... x", "xo./.x./..o x", "ox./.../... o", "x.../.o../..../.... x 3"];
+
+        for &notation in notations.iter() {
+            assert_eq!(notation.to_owned(), Board::from_notation(notation).unwrap().to_notation());
+        }
+    }
+
+    #[test]
+    fn describes_what_is_wrong_with_bad_notation() {
+        assert_eq!(Err(MissingSpaces), Board::from_notation("  "));
+        assert_eq!(Err(MissingSideToMove), Board::from_notation("xo./.x./..o"));
+        assert_eq!(Err(WrongRowLength(1, 2)), Board::from_notation("xo./.x/..o x"));
+        assert_eq!(Err(UnknownSpace('q')), Board::from_notation("xo./.q./..o x"));
+        assert_eq!(Err(UnknownSideToMove(~"z")), Board::from_notation("xo./.x./..o z"));
+        assert_eq!(Err(InvalidWinLength(~"4")), Board::from_notation("xo./.x./..o x 4"));
+        assert_eq!(Err(UnexpectedField(~"extra")), Board::from_notation("xo./.x./..o x 3 extra"));
+    }
+
+    #[test]
+    fn rejects_notation_for_illegal_positions() {
+        assert_eq!(Err(IllegalPosition(ImpossibleTokenCounts(3, 0))), Board::from_notation("xxx/.../... o"));
+        assert_eq!(Err(IllegalPosition(ImpossibleTokenCounts(1, 0))), Board::from_notation("x../.../... x"));
+    }
+}
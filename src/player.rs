@@ -1,32 +1,87 @@
-use ai::*;
 use board::*;
 use console_input::*;
+use strategy::*;
 
+pub static DEFAULT_HINTS: uint = 3;
+
+// A human's hints are shared between clones, so the count lasts the game.
 pub enum Player {
-    HumanPlayer { input: ConsoleInput },
-    ComputerPlayer { ai: AI }
+    HumanPlayer { input: ConsoleInput, hints: @mut uint },
+    ComputerPlayer { strategy: @Strategy }
 }
 
 impl Player {
     pub fn new_human(input: ConsoleInput) -> Player {
-        HumanPlayer { input: input }
+        Player::new_human_with_hints(input, DEFAULT_HINTS)
+    }
+
+    pub fn new_human_with_hints(input: ConsoleInput, hints: uint) -> Player {
+        HumanPlayer { input: input, hints: @mut hints }
     }
 
-    pub fn new_computer(ai: AI) -> Player {
-        ComputerPlayer { ai: ai }
+    pub fn new_computer(strategy: @Strategy) -> Player {
+        ComputerPlayer { strategy: strategy }
     }
 
     pub fn get_move(&self, board: Board) -> Option<int> {
         match *self {
-            HumanPlayer { input: ref input } => input.get_int(),
-            ComputerPlayer { ai: ref ai }    => ai.get_move(board)
+            HumanPlayer { input: ref input, _ }  => input.get_int(),
+            ComputerPlayer { strategy: strategy } => strategy.choose_move(&board)
+        }
+    }
+
+    // Computers only ever move.
+    pub fn get_command(&self, board: Board) -> Option<Command> {
+        match *self {
+            HumanPlayer { input: ref input, _ }  => input.get_command(),
+            ComputerPlayer { strategy: strategy } => strategy.choose_move(&board).map( |&index| Move(index) )
+        }
+    }
+
+    pub fn explain_move(&self, board: &Board) -> Option<~str> {
+        match *self {
+            HumanPlayer { _ }                     => None,
+            ComputerPlayer { strategy: strategy } => strategy.explain(board)
+        }
+    }
+
+    pub fn hints_left(&self) -> uint {
+        match *self {
+            HumanPlayer { hints: hints, _ } => *hints,
+            ComputerPlayer { strategy: _ }  => 0
+        }
+    }
+
+    // Uses up one hint, if there are any left.
+    pub fn take_hint(&self) -> bool {
+        match *self {
+            HumanPlayer { hints: hints, _ } if *hints > 0 => {
+                *hints -= 1;
+                true
+            },
+
+            _ => false
+        }
+    }
+
+    pub fn game_over(&self, outcome: &GameOutcome, token: Token) -> Result<(), ~str> {
+        match *self {
+            HumanPlayer { _ }                     => Ok(()),
+            ComputerPlayer { strategy: strategy } => strategy.game_over(outcome, token)
+        }
+    }
+
+    pub fn is_computer(&self) -> bool {
+        match *self {
+            HumanPlayer { _ }              => false,
+            ComputerPlayer { strategy: _ } => true
         }
     }
 
     pub fn clone(&self) -> Player {
         match *self {
-            HumanPlayer { input: ref input } => HumanPlayer { input: input.clone() },
-            ComputerPlayer { ai: ref ai }    => ComputerPlayer { ai: ai.clone() }
+            HumanPlayer { input: ref input, hints: hints } => HumanPlayer { input: input.clone(), hints: hints },
+            ComputerPlayer { strategy: strategy }          => ComputerPlayer { strategy: strategy }
         }
     }
 }
@@ -38,6 +93,7 @@ mod test {
     use board::*;
     use console_input::*;
     use console_reader::*;
+    use strategy::*;
 
     fn create_human_player_with_mock_input(fake_input: ~str) -> Player {
         let mock_reader = MockReader { str_in_stdin: fake_input };
@@ -57,12 +113,48 @@ mod test {
     }
 
     #[test]
-    fn computer_player_gets_move_from_minimax() {
-        let dumb_ai = AI::new(LowestAvailable);
+    fn computer_player_gets_move_from_its_strategy() {
+        let dumb_ai = @LowestAvailable as @Strategy;
         let player = Player::new_computer(dumb_ai);
         let board = Board::new();
 
         assert_eq!(Some(0), player.get_move(board.clone()));
     }
-}
 
+    #[test]
+    fn knows_which_players_are_computers() {
+        let human = create_human_player_with_mock_input(~"5\n");
+        let computer = Player::new_computer(@LowestAvailable as @Strategy);
+
+        assert!(!human.is_computer());
+        assert!(computer.is_computer());
+    }
+
+    #[test]
+    fn human_player_can_ask_for_a_hint() {
+        let player = create_human_player_with_mock_input(~"hint\n");
+
+        assert_eq!(Some(Hint), player.get_command(Board::new()));
+    }
+
+    #[test]
+    fn computer_player_only_moves() {
+        let player = Player::new_computer(@LowestAvailable as @Strategy);
+
+        assert_eq!(Some(Move(0)), player.get_command(Board::new()));
+        assert!(!player.take_hint());
+    }
+
+    #[test]
+    fn humans_run_out_of_hints() {
+        let mock_input = ConsoleInput { reader: MockReader { str_in_stdin: ~"hint\n" } };
+        let player = Player::new_human_with_hints(mock_input, 2);
+        let copy = player.clone();
+
+        assert!(player.take_hint());
+        assert!(copy.take_hint());
+        assert!(!player.take_hint());
+        assert_eq!(0, player.hints_left());
+        assert_eq!(3, create_human_player_with_mock_input(~"0\n").hints_left());
+    }
+}
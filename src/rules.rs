@@ -0,0 +1,268 @@
+use board::*;
+
+// Newell and Simon's tic-tac-toe rules, in the order they are tried.
+#[deriving(Clone, Eq)]
+pub enum Rule {
+    TakeWin,
+    Block,
+    Fork,
+    BlockFork,
+    Center,
+    OppositeCorner,
+    EmptyCorner,
+    EmptySide
+}
+
+impl Rule {
+    pub fn all() -> ~[Rule] {
+        ~[TakeWin, Block, Fork, BlockFork, Center, OppositeCorner, EmptyCorner, EmptySide]
+    }
+
+    pub fn describe(&self) -> ~str {
+        match *self {
+            TakeWin        => ~"Complete a line to win.",
+            Block          => ~"Block the opponent's line.",
+            Fork           => ~"Make two lines that threaten to win.",
+            BlockFork      => ~"Stop the opponent from making a fork.",
+            Center         => ~"Take the center.",
+            OppositeCorner => ~"Take the corner opposite the opponent.",
+            EmptyCorner    => ~"Take an empty corner.",
+            EmptySide      => ~"Take an empty side."
+        }
+    }
+
+    pub fn moves(&self, board: &Board) -> ~[int] {
+        let token = board.current_token();
+        let opponent = token.other();
+
+        match *self {
+            TakeWin        => board.winning_moves(token),
+            Block          => board.winning_moves(opponent),
+            Fork           => board.fork_moves(token),
+            BlockFork      => block_fork_moves(board),
+            Center         => only_available(board, centers(board.dimension())),
+            OppositeCorner => opposite_corners(board),
+            EmptyCorner    => only_available(board, corners(board.dimension())),
+            EmptySide      => only_available(board, sides(board.dimension()))
+        }
+    }
+}
+
+impl Board {
+    // The first move the rules allow, and the rule that chose it. Boards
+    // larger than the rules were written for can have spaces no rule covers.
+    pub fn rule_based_move(&self) -> Option<(int, Rule)> {
+        if self.is_game_over() {
+            return None;
+        }
+
+        for &rule in Rule::all().iter() {
+            let moves = rule.moves(self);
+
+            if !moves.is_empty() {
+                return Some((moves[0], rule));
+            }
+        }
+
+        None
+    }
+}
+
+// Blocks a single fork outright. Against several, it threatens a win instead,
+// as long as the forced reply doesn't give the opponent a fork of their own.
+fn block_fork_moves(board: &Board) -> ~[int] {
+    let token = board.current_token();
+    let opponent = token.other();
+    let forks = board.fork_moves(opponent);
+
+    if forks.len() <= 1 {
+        return forks;
+    }
+
+    let forcing_moves = board.available_spaces().move_iter().filter( |&index: &int| {
+        let threatening = board.place(index);
+        let threats = threatening.winning_moves(token);
+
+        threats.len() == 1 && threatening.place(threats[0]).winning_moves(opponent).len() < 2
+    }).collect::<~[int]>();
+
+    if forcing_moves.is_empty() { forks } else { forcing_moves }
+}
+
+fn opposite_corners(board: &Board) -> ~[int] {
+    let opponent = board.current_token().other();
+    let last = board.spaces.len() as int - 1;
+
+    corners(board.dimension()).move_iter().filter( |&corner: &int|
+        board.spaces[corner] == Filled(opponent) && board.spaces[last - corner] == Empty
+    ).map( |corner: int| last - corner ).collect()
+}
+
+fn only_available(board: &Board, spaces: ~[int]) -> ~[int] {
+    spaces.move_iter().filter( |&index: &int| board.spaces[index] == Empty ).collect()
+}
+
+fn corners(dimension: uint) -> ~[int] {
+    let dim = dimension as int;
+
+    ~[0, dim - 1, dim * (dim - 1), dim * dim - 1]
+}
+
+// The spaces around the edge that aren't corners.
+fn sides(dimension: uint) -> ~[int] {
+    let dim = dimension as int;
+    let corners = corners(dimension);
+
+    range(0, dim * dim).filter( |&index: &int| {
+        let (row, column) = (index / dim, index % dim);
+        let on_edge = row == 0 || row == dim - 1 || column == 0 || column == dim - 1;
+
+        on_edge && !corners.contains(&index)
+    }).collect()
+}
+
+// One space on an odd board, the middle four on an even one.
+fn centers(dimension: uint) -> ~[int] {
+    let dim = dimension as int;
+    let low = (dim - 1) / 2;
+    let high = dim / 2;
+
+    let mut centers = ~[low * dim + low];
+
+    if high != low {
+        centers.push_all([low * dim + high, high * dim + low, high * dim + high]);
+    }
+
+    centers
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ai::*;
+    use analysis::*;
+    use board::*;
+    use strategy::*;
+
+    fn rule_for(spaces: ~[char]) -> Option<(int, Rule)> {
+        Board::new_from_spaces(spaces).rule_based_move()
+    }
+
+    // Plays `choose` for `token` against every possible reply and checks
+    // that the opponent never wins.
+    fn never_loses(board: Board, token: Token, choose: &fn(&Board) -> Option<int>) {
+        if board.is_game_over() {
+            assert!(board.winner() != Some(token.other()));
+        } else if board.current_token() == token {
+            never_loses(board.place(choose(&board).unwrap()), token, choose);
+        } else {
+            for &index in board.available_spaces().iter() {
+                never_loses(board.place(index), token, choose);
+            }
+        }
+    }
+
+    #[test]
+    fn takes_a_win_before_blocking() {
+        assert_eq!(Some((2, TakeWin)), rule_for(~['x','x',' ',
+                                                  'o','o',' ',
+                                                  ' ',' ',' ' ]));
+    }
+
+    #[test]
+    fn blocks_the_opponent() {
+        assert_eq!(Some((5, Block)), rule_for(~['x',' ',' ',
+                                                'o','o',' ',
+                                                ' ',' ','x' ]));
+    }
+
+    #[test]
+    fn makes_a_fork() {
+        assert_eq!(Some((3, Fork)), rule_for(~[' ',' ',' ',
+                                               ' ',' ','x',
+                                               'x','o','o' ]));
+    }
+
+    #[test]
+    fn blocks_a_fork_by_forcing_a_reply() {
+        assert_eq!(Some((1, BlockFork)), rule_for(~['x',' ',' ',
+                                                    ' ','o',' ',
+                                                    ' ',' ','x' ]));
+    }
+
+    #[test]
+    fn takes_the_center_then_the_opposite_corner() {
+        assert_eq!(Some((4, Center)), Board::new().rule_based_move());
+        assert_eq!(Some((8, OppositeCorner)), rule_for(~['o',' ',' ',
+                                                         ' ','x',' ',
+                                                         ' ',' ',' ' ]));
+    }
+
+    #[test]
+    fn takes_only_sides_that_are_not_corners() {
+        assert_eq!(~[3, 5, 7], EmptySide.moves(&Board::new().place(1)));
+        assert_eq!(~[1, 2, 4, 7, 8, 11, 13, 14], EmptySide.moves(&Board::with_size(4)));
+        assert_eq!(~[], EmptySide.moves(&Board::new_from_spaces(~[' ','x',' ',
+                                                                  'o','x','x',
+                                                                  ' ','o',' ' ])));
+    }
+
+    #[test]
+    fn has_no_move_when_the_game_is_over() {
+        assert_eq!(None, rule_for(~['x','x','x',
+                                    'o','o',' ',
+                                    ' ',' ',' ' ]));
+    }
+
+    #[test]
+    fn describes_every_rule() {
+        for rule in Rule::all().iter() {
+            assert!(rule.describe().len() > 0);
+        }
+    }
+
+    #[test]
+    fn rule_based_play_never_loses() {
+        let choose = |board: &Board| board.rule_based_move().map( |&(index, _)| index );
+
+        for &first in [X, O].iter() {
+            never_loses(Board::new().with_to_move(first), X, choose);
+            never_loses(Board::new().with_to_move(first), O, choose);
+        }
+    }
+
+    // Follows the rules for `token` against every possible reply, and checks
+    // that Minimax's move is worth at least as much as the rules' in every
+    // position they reach.
+    fn minimax_does_as_well(board: Board, token: Token, ai: &AI) {
+        if board.is_game_over() {
+            return;
+        }
+
+        if board.current_token() == token {
+            let analyses = ai.analyze(&board);
+            let value_of = |index: int| analyses.iter().find( |analysis: &&MoveAnalysis| analysis.index == index )
+                                                .unwrap().value.rank();
+
+            let rule_move = RuleBased.choose_move(&board).unwrap();
+            let minimax_move = Minimax::with_ai(ai.clone()).choose_move(&board).unwrap();
+
+            assert!(value_of(minimax_move) >= value_of(rule_move));
+            minimax_does_as_well(board.place(rule_move), token, ai);
+        } else {
+            for &index in board.available_spaces().iter() {
+                minimax_does_as_well(board.place(index), token, ai);
+            }
+        }
+    }
+
+    #[test]
+    fn minimax_never_does_worse_than_the_rules() {
+        let ai = AI::new();
+
+        for &first in [X, O].iter() {
+            minimax_does_as_well(Board::new().with_to_move(first), X, &ai);
+            minimax_does_as_well(Board::new().with_to_move(first), O, &ai);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::ascii::StrAsciiExt;
+
+use ai::*;
+use board::*;
+use difficulty::*;
+use evaluator::*;
+use mcts::*;
+use tablebase::*;
+
+// Monte Carlo's budget when a game has to replay from its seed.
+static REPLAYABLE_ITERATIONS: uint = 10000;
+
+// How a computer player picks its moves. Strategies in other modules only
+// need to implement this and be registered to show up in the menu.
+pub trait Strategy {
+    fn name(&self) -> ~str;
+
+    fn choose_move(&self, board: &Board) -> Option<int>;
+
+    // Why the strategy would choose its move here, if it can say.
+    fn explain(&self, _board: &Board) -> Option<~str> {
+        None
+    }
+
+    // Strategies that learn from experience hear how each game went, and
+    // report anything that went wrong keeping what they learned.
+    fn game_over(&self, _outcome: &GameOutcome, _token: Token) -> Result<(), ~str> {
+        Ok(())
+    }
+}
+
+// The computer players on offer, in menu order. Each player gets a fresh
+// strategy from its factory, and strategies that need random numbers get
+// them from the engine.
+pub struct Registry {
+    entries: ~[(~str, @fn() -> @Strategy)],
+    engine: @fn() -> AI
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry::with_engine(|| AI::new())
+    }
+
+    pub fn with_engine(engine: @fn() -> AI) -> Registry {
+        Registry { entries: ~[], engine: engine }
+    }
+
+    // The difficulty levels, then the other strategies that need no files.
+    pub fn with_builtins() -> Registry {
+        Registry::builtins(|| AI::new(), false)
+    }
+
+    // The same strategies, breaking ties between equally good moves at
+    // random. They share one seeded engine, so each game differs but a run
+    // with the same seed and the same moves can be replayed.
+    pub fn with_random_ties(seed: u32) -> Registry {
+        let ai = AI::seeded(seed);
+
+        Registry::builtins(|| ai.clone(), true)
+    }
+
+    // Searches on a clock can't replay, since how far they get depends on
+    // timing. Replayable registries give Monte Carlo a fixed number of
+    // iterations, and Time limited its own random numbers so the moves it
+    // picks never shift what the other players draw.
+    fn builtins(engine: @fn() -> AI, replayable: bool) -> Registry {
+        let mut registry = Registry::with_engine(engine);
+
+        for &difficulty in Difficulty::all().iter() {
+            registry.register(difficulty.name(), || difficulty.strategy_with(engine()));
+        }
+
+        registry.register("Lowest available", || @LowestAvailable as @Strategy);
+        registry.register("Rule based", || @RuleBased as @Strategy);
+        let monte_carlo_budget = if replayable { Iterations(REPLAYABLE_ITERATIONS) } else { Milliseconds(1000) };
+        let time_limited_engine: @fn() -> AI = if replayable { || engine().forked() } else { engine };
+
+        registry.register("Monte Carlo", || @MonteCarlo::with_ai(engine(), monte_carlo_budget, DEFAULT_EXPLORATION) as @Strategy);
+        registry.register("Depth limited", || @DepthLimited::with_ai(engine(), 4, @LineEvaluator::new() as @Evaluator) as @Strategy);
+        registry.register("Time limited", || @TimeLimited::with_ai(time_limited_engine(), 1000, @LineEvaluator::new() as @Evaluator) as @Strategy);
+
+        // Solved the first time it is chosen, then shared by every player in
+        // every game.
+        let tablebase: @mut Option<@Tablebase> = @mut None;
+
+        registry.register("Tablebase", || {
+            if tablebase.is_none() {
+                *tablebase = Some(@Tablebase::build_with_ai(engine()));
+            }
+
+            (*tablebase).unwrap() as @Strategy
+        });
+
+        registry
+    }
+
+    // For strategies built outside the registry, so they share its seed.
+    pub fn engine(&self) -> AI {
+        (self.engine)()
+    }
+
+    pub fn register(&mut self, name: &str, factory: @fn() -> @Strategy) {
+        self.entries.push((name.to_owned(), factory));
+    }
+
+    pub fn len(&self) -> uint {
+        self.entries.len()
+    }
+
+    pub fn names(&self) -> ~[~str] {
+        self.entries.map( |&(ref name, _)| name.clone() )
+    }
+
+    // Menu choices count from 1, in the order strategies were registered.
+    pub fn from_choice(&self, choice: int) -> Option<@Strategy> {
+        if choice >= 1 && choice <= self.len() as int {
+            let (_, ref factory) = self.entries[choice - 1];
+            Some((*factory)())
+        } else {
+            None
+        }
+    }
+
+    pub fn find(&self, name: &str) -> Option<@Strategy> {
+        let wanted = name.to_ascii_lower();
+
+        self.entries.iter().find( |&&(ref entry, _)| entry.to_ascii_lower() == wanted )
+                           .map( |&&(_, factory)| factory() )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use board::*;
+
+    struct Stubborn;
+
+    impl Strategy for Stubborn {
+        fn name(&self) -> ~str {
+            ~"Stubborn"
+        }
+
+        fn choose_move(&self, _: &Board) -> Option<int> {
+            Some(4)
+        }
+    }
+
+    #[test]
+    fn lists_the_builtin_strategies_in_menu_order() {
+        let names = Registry::with_builtins().names();
+
+        assert_eq!(~[~"Random", ~"Easy", ~"Medium", ~"Perfect", ~"Lowest available",
+                     ~"Rule based", ~"Monte Carlo", ~"Depth limited", ~"Time limited", ~"Tablebase"], names);
+    }
+
+    #[test]
+    fn builds_a_strategy_from_a_menu_choice() {
+        let registry = Registry::with_builtins();
+
+        assert_eq!(~"Perfect", registry.from_choice(4).unwrap().name());
+        assert!(registry.from_choice(0).is_none());
+        assert!(registry.from_choice(registry.len() as int + 1).is_none());
+    }
+
+    #[test]
+    fn offers_the_same_strategies_with_random_ties() {
+        let registry = Registry::with_random_ties(7);
+        let perfect = registry.from_choice(4).unwrap();
+
+        assert_eq!(Registry::with_builtins().names(), registry.names());
+        assert_eq!(~"Perfect", perfect.name());
+        assert_eq!(Some(8), perfect.choose_move(&Board::new_from_spaces(~[' ','o','x',
+                                                                          ' ','o','x',
+                                                                          ' ',' ',' ' ])));
+    }
+
+    #[test]
+    fn replays_monte_carlo_games_from_the_same_seed() {
+        let play_game = |seed: u32| {
+            let monte_carlo = Registry::with_random_ties(seed).find("Monte Carlo").unwrap();
+            let mut board = Board::new();
+            let mut moves = ~[];
+
+            while !board.is_game_over() {
+                let index = monte_carlo.choose_move(&board).unwrap();
+
+                board = board.place(index);
+                moves.push(index);
+            }
+
+            moves
+        };
+
+        assert_eq!(play_game(7), play_game(7));
+    }
+
+    #[test]
+    fn registers_strategies_from_other_modules() {
+        let mut registry = Registry::new();
+        registry.register("Stubborn", || @Stubborn as @Strategy);
+
+        let strategy = registry.find("stubborn").unwrap();
+
+        assert_eq!(Some(4), strategy.choose_move(&Board::new()));
+        assert!(registry.find("Minimax").is_none());
+    }
+}
@@ -0,0 +1,91 @@
+use board::*;
+
+#[deriving(Clone, Eq)]
+pub struct Symbols {
+    x: char,
+    o: char,
+    empty: char
+}
+
+impl Symbols {
+    pub fn new(x: char, o: char, empty: char) -> Symbols {
+        Symbols { x: x,
+                  o: o,
+                  empty: empty }
+    }
+
+    pub fn default() -> Symbols {
+        Symbols::new('x', 'o', ' ')
+    }
+
+    pub fn token_to_char(&self, token: Token) -> char {
+        match token {
+            X => self.x,
+            O => self.o
+        }
+    }
+
+    pub fn cell_to_char(&self, cell: Cell) -> char {
+        match cell {
+            Empty         => self.empty,
+            Filled(token) => self.token_to_char(token)
+        }
+    }
+
+    pub fn char_to_cell(&self, symbol: char) -> Option<Cell> {
+        if symbol == self.x {
+            Some(Filled(X))
+        } else if symbol == self.o {
+            Some(Filled(O))
+        } else if symbol == self.empty {
+            Some(Empty)
+        } else {
+            None
+        }
+    }
+
+    pub fn chars_to_cells(&self, symbols: &[char]) -> Option<~[Cell]> {
+        let mut cells = ~[];
+
+        for &symbol in symbols.iter() {
+            match self.char_to_cell(symbol) {
+                Some(cell) => cells.push(cell),
+                None       => return None
+            }
+        }
+
+        Some(cells)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use board::*;
+
+    #[test]
+    fn maps_tokens_and_cells_to_their_symbols() {
+        let symbols = Symbols::new('X', 'O', '.');
+
+        assert_eq!('X', symbols.token_to_char(X));
+        assert_eq!('O', symbols.cell_to_char(Filled(O)));
+        assert_eq!('.', symbols.cell_to_char(Empty));
+    }
+
+    #[test]
+    fn maps_symbols_back_to_cells() {
+        let symbols = Symbols::default();
+
+        assert_eq!(Some(Filled(X)), symbols.char_to_cell('x'));
+        assert_eq!(Some(Empty), symbols.char_to_cell(' '));
+        assert_eq!(None, symbols.char_to_cell('q'));
+    }
+
+    #[test]
+    fn rejects_spaces_with_unknown_symbols() {
+        let symbols = Symbols::default();
+
+        assert_eq!(Some(~[Filled(X), Empty, Filled(O)]), symbols.chars_to_cells(['x', ' ', 'o']));
+        assert_eq!(None, symbols.chars_to_cells(['x', 'q', 'o']));
+    }
+}
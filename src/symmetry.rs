@@ -0,0 +1,208 @@
+use board::*;
+
+// One of the eight symmetries of a square board: an optional left-to-right
+// mirror followed by a number of clockwise quarter turns.
+#[deriving(Clone, Eq)]
+pub struct Transform {
+    rotations: uint,
+    reflected: bool
+}
+
+impl Transform {
+    pub fn identity() -> Transform {
+        Transform { rotations: 0, reflected: false }
+    }
+
+    pub fn all() -> ~[Transform] {
+        let mut transforms = ~[];
+
+        for &reflected in [false, true].iter() {
+            for rotations in range(0u, 4) {
+                transforms.push(Transform { rotations: rotations, reflected: reflected });
+            }
+        }
+
+        transforms
+    }
+
+    pub fn apply(&self, index: int, dimension: uint) -> int {
+        let dim = dimension as int;
+        let mut row = index / dim;
+        let mut column = index % dim;
+
+        if self.reflected {
+            column = dim - 1 - column;
+        }
+
+        do self.rotations.times {
+            let rotated_row = column;
+            column = dim - 1 - row;
+            row = rotated_row;
+        }
+
+        row * dim + column
+    }
+
+    pub fn inverse(&self) -> Transform {
+        if self.reflected {
+            *self
+        } else {
+            Transform { rotations: (4 - self.rotations) % 4, reflected: false }
+        }
+    }
+}
+
+impl Board {
+    pub fn transformed(&self, transform: Transform) -> Board {
+        let dim = self.dimension();
+        let mut new_spaces = self.spaces.clone();
+
+        for (index, &cell) in self.spaces.iter().enumerate() {
+            new_spaces[transform.apply(index as int, dim)] = cell;
+        }
+
+        Board { spaces: new_spaces,
+                win_length: self.win_length,
+                to_move: self.to_move,
+                flash_message: None }
+    }
+
+    // The smallest of the board's symmetric forms, and the transform that
+    // turns it back into this board.
+    pub fn canonical(&self) -> (Board, Transform) {
+        let mut best = self.with_flash_message(None);
+        let mut best_transform = Transform::identity();
+
+        for &transform in Transform::all().iter() {
+            let candidate = self.transformed(transform);
+
+            if candidate.symmetry_key() < best.symmetry_key() {
+                best = candidate;
+                best_transform = transform;
+            }
+        }
+
+        (best, best_transform.inverse())
+    }
+
+    pub fn distinct_moves(&self) -> ~[int] {
+        let dim = self.dimension();
+        let symmetries = Transform::all().move_iter().filter( |&transform: &Transform|
+            self.transformed(transform).spaces == self.spaces
+        ).collect::<~[Transform]>();
+
+        let mut moves: ~[int] = ~[];
+
+        for &index in self.available_spaces().iter() {
+            let is_duplicate = moves.iter().any( |&kept: &int|
+                symmetries.iter().any( |transform: &Transform| transform.apply(kept, dim) == index )
+            );
+
+            if !is_duplicate {
+                moves.push(index);
+            }
+        }
+
+        moves
+    }
+
+    fn symmetry_key(&self) -> ~[uint] {
+        self.spaces.iter().map( |&cell: &Cell|
+            match cell {
+                Empty     => 0u,
+                Filled(X) => 1u,
+                Filled(O) => 2u
+            }
+        ).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use board::*;
+
+    #[test]
+    fn has_eight_distinct_transforms() {
+        let transforms = Transform::all();
+
+        assert_eq!(8, transforms.len());
+
+        for (i, first) in transforms.iter().enumerate() {
+            for second in transforms.slice_from(i + 1).iter() {
+                let images = range(0, 9).map( |index: int| first.apply(index, 3) ).collect::<~[int]>();
+                let other_images = range(0, 9).map( |index: int| second.apply(index, 3) ).collect::<~[int]>();
+
+                assert!(images != other_images);
+            }
+        }
+    }
+
+    #[test]
+    fn rotates_and_reflects_the_board() {
+        let board = Board::new_from_spaces(~['x','o',' ',
+                                             ' ',' ',' ',
+                                             ' ',' ',' ' ]);
+
+        let rotated = Board::new_from_spaces(~[' ',' ','x',
+                                               ' ',' ','o',
+                                               ' ',' ',' ' ]);
+
+        let reflected = Board::new_from_spaces(~[' ','o','x',
+                                                 ' ',' ',' ',
+                                                 ' ',' ',' ' ]);
+
+        assert_eq!(rotated, board.transformed(Transform { rotations: 1, reflected: false }));
+        assert_eq!(reflected, board.transformed(Transform { rotations: 0, reflected: true }));
+    }
+
+    #[test]
+    fn inverse_undoes_every_transform() {
+        let board = Board::new_from_spaces(~['x','o',' ',' ',
+                                             ' ',' ','x',' ',
+                                             ' ','o',' ',' ',
+                                             ' ',' ',' ',' ' ]);
+
+        for &transform in Transform::all().iter() {
+            assert_eq!(board, board.transformed(transform).transformed(transform.inverse()));
+        }
+    }
+
+    #[test]
+    fn symmetric_boards_share_a_canonical_form() {
+        let corner = Board::new_from_spaces(~['x',' ',' ',
+                                              ' ',' ',' ',
+                                              ' ',' ',' ' ]);
+
+        let other_corner = Board::new_from_spaces(~[' ',' ',' ',
+                                                    ' ',' ',' ',
+                                                    ' ',' ','x' ]);
+
+        let (canonical, _) = corner.canonical();
+        let (other_canonical, _) = other_corner.canonical();
+
+        assert_eq!(canonical, other_canonical);
+    }
+
+    #[test]
+    fn canonical_transform_maps_back_to_the_board() {
+        let board = Board::new_from_spaces(~[' ',' ','o',
+                                             ' ','x',' ',
+                                             'x',' ',' ' ]);
+
+        let (canonical, transform) = board.canonical();
+
+        assert_eq!(board, canonical.transformed(transform));
+    }
+
+    #[test]
+    fn finds_the_symmetrically_distinct_moves() {
+        let center_taken = Board::new_from_spaces(~[' ',' ',' ',
+                                                    ' ','x',' ',
+                                                    ' ',' ',' ' ]);
+
+        assert_eq!(~[0, 1, 4], Board::new().distinct_moves());
+        assert_eq!(~[0, 1], center_taken.distinct_moves());
+        assert_eq!(~[0, 1, 5], Board::with_size(4).distinct_moves());
+    }
+}
@@ -0,0 +1,193 @@
+use std::hashmap::HashMap;
+
+use ai::*;
+use analysis::*;
+use board::*;
+use notation::*;
+use strategy::*;
+
+pub struct TablebaseEntry {
+    value: MoveValue,
+    best_moves: ~[int]
+}
+
+// Every position of standard tic-tac-toe that is still being played, with
+// its value for the player to move and all the moves that keep it. Solved
+// once by a full search, so looking up a move is a single hash probe. The
+// engine breaks ties between the best moves.
+pub struct Tablebase {
+    positions: HashMap<~str, TablebaseEntry>,
+    ai: AI
+}
+
+impl Tablebase {
+    pub fn build() -> Tablebase {
+        Tablebase::build_with_ai(AI::new())
+    }
+
+    pub fn build_with_ai(ai: AI) -> Tablebase {
+        let mut tablebase = Tablebase { positions: HashMap::new(), ai: ai };
+
+        tablebase.solve(&Board::new());
+        tablebase.solve(&Board::new().with_to_move(O));
+
+        tablebase
+    }
+
+    pub fn len(&self) -> uint {
+        self.positions.len()
+    }
+
+    // Finished games and positions from other board sizes aren't in the table.
+    pub fn find<'a>(&'a self, board: &Board) -> Option<&'a TablebaseEntry> {
+        self.positions.find(&board.to_notation())
+    }
+
+    fn solve(&mut self, board: &Board) -> MoveValue {
+        let key = board.to_notation();
+
+        match self.positions.find(&key) {
+            Some(entry) => return entry.value.clone(),
+            None        => ()
+        }
+
+        let mut best_value: Option<MoveValue> = None;
+        let mut best_moves = ~[];
+
+        for &index in board.available_spaces().iter() {
+            let value = self.value_of_move(board, index);
+
+            let is_better = match best_value {
+                Some(ref best) => value.rank() > best.rank(),
+                None           => true
+            };
+
+            if is_better {
+                best_value = Some(value);
+                best_moves = ~[index];
+            } else if best_value == Some(value) {
+                best_moves.push(index);
+            }
+        }
+
+        // Only positions still in play are solved, so there is always a move.
+        let best_value = best_value.unwrap();
+
+        self.positions.insert(key, TablebaseEntry { value: best_value.clone(), best_moves: best_moves });
+
+        best_value
+    }
+
+    fn value_of_move(&mut self, board: &Board, index: int) -> MoveValue {
+        let new_board = board.place(index);
+
+        match new_board.outcome() {
+            Win { _ }  => WinIn(1),
+            Draw       => Drawn,
+            InProgress => match self.solve(&new_board) {
+                WinIn(moves)  => LossIn(moves + 1),
+                Drawn         => Drawn,
+                Unknown       => Unknown,
+                LossIn(moves) => WinIn(moves + 1)
+            }
+        }
+    }
+}
+
+impl Strategy for Tablebase {
+    fn name(&self) -> ~str {
+        ~"Tablebase"
+    }
+
+    fn choose_move(&self, board: &Board) -> Option<int> {
+        match self.find(board) {
+            Some(entry) => self.ai.break_tie(entry.best_moves),
+            None        => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ai::*;
+    use analysis::*;
+    use board::*;
+    use notation::*;
+    use strategy::*;
+
+    #[test]
+    fn holds_every_reachable_position_still_in_play() {
+        assert_eq!(2 * 4520, Tablebase::build().len());
+    }
+
+    #[test]
+    fn knows_the_value_and_best_moves_of_a_position() {
+        let tablebase = Tablebase::build();
+        let fork_board = Board::new_from_spaces(~['x',' ',' ',
+                                                  'o','x',' ',
+                                                  ' ',' ','o' ]);
+
+        let entry = tablebase.find(&fork_board).unwrap();
+
+        assert_eq!(Drawn, tablebase.find(&Board::new()).unwrap().value);
+        assert_eq!(WinIn(3), entry.value);
+        assert_eq!(~[1, 2], entry.best_moves);
+        assert_eq!(Some(1), tablebase.choose_move(&fork_board));
+    }
+
+    #[test]
+    fn picks_among_the_best_moves_at_random_when_seeded() {
+        let tablebase = Tablebase::build_with_ai(AI::seeded(7));
+        let mut picked = ~[];
+
+        do 20.times {
+            let index = tablebase.choose_move(&Board::new()).unwrap();
+
+            if !picked.contains(&index) {
+                picked.push(index);
+            }
+        }
+
+        assert!(picked.len() > 1);
+        assert!(picked.iter().all( |index: &int| tablebase.find(&Board::new()).unwrap().best_moves.contains(index) ));
+    }
+
+    #[test]
+    fn keeps_the_moves_of_a_lost_position() {
+        let tablebase = Tablebase::build();
+        let lost_board = Board::new_from_spaces(~['x','x',' ',
+                                                  'o','x',' ',
+                                                  ' ',' ','o' ]);
+
+        let entry = tablebase.find(&lost_board).unwrap();
+
+        assert_eq!(LossIn(2), entry.value);
+        assert_eq!(~[2, 5, 6, 7], entry.best_moves);
+        assert_eq!(Some(2), tablebase.choose_move(&lost_board));
+    }
+
+    #[test]
+    fn has_no_entry_for_a_finished_game() {
+        let tablebase = Tablebase::build();
+        let x_won = Board::new_from_spaces(~['x','x','x',
+                                             'o','o',' ',
+                                             ' ',' ',' ' ]);
+
+        assert!(tablebase.find(&x_won).is_none());
+        assert_eq!(None, tablebase.choose_move(&x_won));
+    }
+
+    #[test]
+    fn minimax_plays_a_best_move_in_every_position() {
+        let tablebase = Tablebase::build();
+        let minimax = Minimax::new();
+
+        for (notation, entry) in tablebase.positions.iter() {
+            let board = Board::from_notation(*notation).unwrap();
+            let index = minimax.choose_move(&board).unwrap();
+
+            assert!(entry.best_moves.contains(&index));
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::hashmap::HashMap;
+
+// Wins and losses score a multiple of this, leaving everything in between
+// for heuristic scores of unfinished positions.
+pub static WIN_SCALE: int = 1000;
+
+#[deriving(Clone, Eq)]
+pub enum Bound {
+    Exact,
+    LowerBound,
+    UpperBound
+}
+
+#[deriving(Clone, Eq)]
+pub struct Entry {
+    score: int,
+    bound: Bound,
+    draft: int
+}
+
+pub struct TranspositionTable {
+    entries: HashMap<u64, Entry>
+}
+
+impl TranspositionTable {
+    pub fn new() -> TranspositionTable {
+        TranspositionTable { entries: HashMap::new() }
+    }
+
+    pub fn find(&self, hash: u64) -> Option<Entry> {
+        match self.entries.find(&hash) {
+            Some(entry) => Some(*entry),
+            None        => None
+        }
+    }
+
+    pub fn store(&mut self, hash: u64, entry: Entry) {
+        self.entries.insert(hash, entry);
+    }
+
+    pub fn len(&self) -> uint {
+        self.entries.len()
+    }
+}
+
+// Win scores count down with the depth of the finishing move, so they are
+// stored relative to the position and shifted back to the depth it is found at.
+pub fn to_table_score(score: int, depth: int) -> int {
+    if score >= WIN_SCALE {
+        score + depth * WIN_SCALE
+    } else if score <= -WIN_SCALE {
+        score - depth * WIN_SCALE
+    } else {
+        score
+    }
+}
+
+pub fn from_table_score(score: int, depth: int) -> int {
+    if score >= WIN_SCALE {
+        score - depth * WIN_SCALE
+    } else if score <= -WIN_SCALE {
+        score + depth * WIN_SCALE
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stores_and_finds_entries() {
+        let mut table = TranspositionTable::new();
+        let entry = Entry { score: 5, bound: LowerBound, draft: 2 };
+
+        table.store(42, entry);
+
+        assert_eq!(Some(entry), table.find(42));
+        assert_eq!(None, table.find(7));
+        assert_eq!(1, table.len());
+    }
+
+    #[test]
+    fn replaces_entries_for_the_same_position() {
+        let mut table = TranspositionTable::new();
+
+        table.store(42, Entry { score: 5, bound: LowerBound, draft: 2 });
+        table.store(42, Entry { score: 3, bound: Exact, draft: 4 });
+
+        assert_eq!(Some(Entry { score: 3, bound: Exact, draft: 4 }), table.find(42));
+    }
+
+    #[test]
+    fn stores_win_scores_relative_to_the_position() {
+        assert_eq!(7000, to_table_score(5000, 2));
+        assert_eq!(-7000, to_table_score(-5000, 2));
+        assert_eq!(0, to_table_score(0, 2));
+
+        assert_eq!(4000, from_table_score(to_table_score(5000, 2), 3));
+        assert_eq!(-4000, from_table_score(to_table_score(-5000, 2), 3));
+    }
+
+    #[test]
+    fn stores_heuristic_scores_unchanged() {
+        assert_eq!(25, to_table_score(25, 2));
+        assert_eq!(-999, from_table_score(-999, 3));
+    }
+}
@@ -0,0 +1,205 @@
+use std::num::sqrt;
+
+use board::*;
+use symbols::*;
+
+#[deriving(Clone, Eq)]
+pub enum PositionError {
+    NotSquare(uint),
+    UnknownSymbol(char),
+    WinLengthOutOfRange(uint),
+    ImpossibleTokenCounts(uint, uint),
+    BothPlayersWon,
+    PlayedAfterWin(Token)
+}
+
+impl Board {
+    pub fn new_validated(spaces: ~[char]) -> Result<Board, PositionError> {
+        let symbols = Symbols::default();
+        let mut cells = ~[];
+
+        for &symbol in spaces.iter() {
+            match symbols.char_to_cell(symbol) {
+                Some(cell) => cells.push(cell),
+                None       => return Err(UnknownSymbol(symbol))
+            }
+        }
+
+        if !is_square(cells.len()) {
+            return Err(NotSquare(cells.len()));
+        }
+
+        let board = Board::new_from_cells(cells);
+
+        // With equal counts either player may have started, so the side to
+        // move worked out from the counts isn't the only one to try.
+        match board.check_position() {
+            None        => Ok(board),
+            Some(error) => {
+                let other_side = board.with_to_move(board.current_token().other());
+
+                match other_side.check_position() {
+                    None    => Ok(other_side),
+                    Some(_) => Err(error)
+                }
+            }
+        }
+    }
+
+    // Describes why this position could not have come up in a game, where
+    // either player may have started.
+    pub fn check_position(&self) -> Option<PositionError> {
+        if !is_square(self.spaces.len()) {
+            return Some(NotSquare(self.spaces.len()));
+        }
+
+        if self.win_length == 0 || self.win_length > self.dimension() {
+            return Some(WinLengthOutOfRange(self.win_length));
+        }
+
+        let to_move = self.current_token();
+        let just_moved = to_move.other();
+
+        if self.count(just_moved) != self.count(to_move) && self.count(just_moved) != self.count(to_move) + 1 {
+            return Some(ImpossibleTokenCounts(self.count(X), self.count(O)));
+        }
+
+        let x_lines = self.lines_of(X);
+        let o_lines = self.lines_of(O);
+
+        if x_lines.len() > 0 && o_lines.len() > 0 {
+            return Some(BothPlayersWon);
+        }
+
+        let (winner, winning_lines) = if x_lines.len() > 0 { (X, x_lines) } else { (O, o_lines) };
+
+        if winning_lines.len() > 0 && (winner == to_move || !share_a_space(winning_lines)) {
+            return Some(PlayedAfterWin(winner));
+        }
+
+        None
+    }
+
+    fn count(&self, token: Token) -> uint {
+        self.spaces.iter().count( |cell: &Cell| *cell == Filled(token) )
+    }
+
+    fn lines_of(&self, token: Token) -> ~[~[int]] {
+        self.lines().move_iter().filter( |line: &~[int]|
+            line.iter().all( |&index: &int| self.spaces[index] == Filled(token) )
+        ).collect()
+    }
+}
+
+fn is_square(size: uint) -> bool {
+    let dimension = sqrt(size as float) as uint;
+
+    size > 0 && dimension * dimension == size
+}
+
+// Every winning line must run through the space of the final move.
+fn share_a_space(lines: &[~[int]]) -> bool {
+    lines[0].iter().any( |index: &int| lines.iter().all( |line: &~[int]| line.contains(index) ) )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use board::*;
+
+    #[test]
+    fn accepts_positions_that_can_occur_in_play() {
+        let positions = [~[' ',' ',' ',
+                           ' ',' ',' ',
+                           ' ',' ',' ' ],
+
+                         ~['o',' ',' ',
+                           ' ',' ',' ',
+                           ' ',' ',' ' ],
+
+                         ~['x','x','x',
+                           'o','o',' ',
+                           ' ',' ',' ' ],
+
+                         ~['x','o','x',
+                           'o','x','o',
+                           'o','x','x' ]];
+
+        for spaces in positions.iter() {
+            assert!(Board::new_validated(spaces.clone()).is_ok());
+        }
+    }
+
+    #[test]
+    fn rejects_spaces_that_do_not_form_a_square() {
+        assert_eq!(Err(NotSquare(8)), Board::new_validated(~['x','o',' ',' ',' ',' ',' ',' ']));
+        assert_eq!(Err(NotSquare(0)), Board::new_validated(~[]));
+    }
+
+    #[test]
+    fn rejects_unknown_symbols() {
+        assert_eq!(Err(UnknownSymbol('q')), Board::new_validated(~['x','q',' ',
+                                                                   ' ',' ',' ',
+                                                                   ' ',' ',' ' ]));
+    }
+
+    #[test]
+    fn rejects_impossible_token_counts() {
+        assert_eq!(Err(ImpossibleTokenCounts(4, 0)), Board::new_validated(~['x','x',' ',
+                                                                            'x','x',' ',
+                                                                            ' ',' ',' ' ]));
+
+        let wrong_side_to_move = Board::new_from_spaces(~['x',' ',' ',
+                                                          ' ',' ',' ',
+                                                          ' ',' ',' ' ]).with_to_move(X);
+
+        assert_eq!(Some(ImpossibleTokenCounts(1, 0)), wrong_side_to_move.check_position());
+    }
+
+    #[test]
+    fn rejects_positions_where_both_players_won() {
+        assert_eq!(Err(BothPlayersWon), Board::new_validated(~['x','x','x',
+                                                               'o','o','o',
+                                                               ' ',' ',' ' ]));
+    }
+
+    #[test]
+    fn rejects_positions_played_after_a_win() {
+        let o_moved_after_x_won = Board::new_validated(~['x','x','x',
+                                                         'o','o',' ',
+                                                         'o','o',' ' ]);
+
+        let two_separate_wins = Board::new_from_spaces(~['x','x','x',' ',' ',
+                                                         'o',' ','o',' ',' ',
+                                                         ' ',' ',' ','o',' ',
+                                                         ' ','o',' ','o',' ',
+                                                         'x','x','x',' ',' ' ]).with_win_length(3);
+
+        assert_eq!(Err(PlayedAfterWin(X)), o_moved_after_x_won);
+        assert_eq!(Some(PlayedAfterWin(X)), two_separate_wins.check_position());
+    }
+
+    #[test]
+    fn accepts_a_win_on_the_last_move_when_o_started() {
+        let x_won_second = Board::new_validated(~['x','x','x',
+                                                  'o','o',' ',
+                                                  'o',' ',' ' ]).unwrap();
+
+        assert_eq!(O, x_won_second.current_token());
+        assert_eq!(Some(PlayedAfterWin(X)), x_won_second.with_to_move(X).check_position());
+    }
+
+    #[test]
+    fn accepts_a_final_move_that_completes_two_lines() {
+        let double_win = Board::new_validated(~['x','x','x',
+                                                'o','x','o',
+                                                'x','o','o' ]);
+
+        assert!(double_win.is_ok());
+    }
+
+    #[test]
+    fn rejects_an_invalid_win_length() {
+        assert_eq!(Some(WinLengthOutOfRange(4)), Board::new().with_win_length(4).check_position());
+    }
+}